@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use super::Result;
+
+/// Run an operation over many containers with limited parallelism
+///
+/// # Arguments
+///
+/// * `names` - The names of the containers to operate on
+/// * `parallelism` - The maximum number of operations in flight at once
+/// * `f` - The operation to run for each container name
+///
+/// # Return
+///
+/// The result of the operation for each container, in the same order as `names`
+///
+/// # Example
+///
+/// ```
+/// use lxd::batch;
+///
+/// let results = batch(&["a", "b", "c"], 2, |name| Ok(name.to_uppercase()));
+/// let names: Vec<String> = results.into_iter().map(|result| result.unwrap()).collect();
+/// assert_eq!(names, ["A", "B", "C"]);
+/// ```
+pub fn batch<T, F>(names: &[&str], parallelism: usize, f: F) -> Vec<Result<T>>
+    where T: Send, F: Fn(&str) -> Result<T> + Sync
+{
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<Result<T>>>> = names.iter().map(|_| Mutex::new(None)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..parallelism.clamp(1, names.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if i >= names.len() {
+                    break;
+                }
+
+                let result = f(names[i]);
+                *results[i].lock().unwrap() = Some(result);
+            });
+        }
+    });
+
+    results.into_iter().map(|result| {
+        result.into_inner().unwrap().expect("batch operation did not run")
+    }).collect()
+}
@@ -0,0 +1,485 @@
+use std::time::Duration;
+
+use super::{lxc, lxc_input, Container, DeviceKind, Error, Location, Result};
+
+/// The image to create a container from
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BaseImage {
+    /// An image on the host of the container, by fingerprint
+    Fingerprint(String),
+    /// An image on a remote, by alias, `ubuntu:16.04` for example
+    Remote {
+        remote: String,
+        alias: String,
+    },
+    /// An image on the host of the container, by alias
+    Local(String),
+}
+
+impl BaseImage {
+    /// Get the image source argument for `lxc launch`
+    ///
+    /// Images on the host of the container are prefixed with its remote, so
+    /// that they are looked up there rather than on the default remote.
+    pub(crate) fn source(&self, location: &Location) -> Result<String> {
+        let invalid = |message: String| Err(Error::InvalidInput(
+            format!("LXD image: {}", message)
+        ));
+
+        let name = match *self {
+            BaseImage::Fingerprint(ref fingerprint) => {
+                if fingerprint.is_empty() || !fingerprint.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return invalid(format!("{:?} is not a valid fingerprint", fingerprint));
+                }
+                fingerprint
+            },
+            BaseImage::Remote { ref remote, ref alias } => {
+                if remote.is_empty() || remote.contains(':') || alias.is_empty() {
+                    return invalid(format!("{:?} is not a valid remote image", self));
+                }
+                return Ok(format!("{}:{}", remote, alias));
+            },
+            BaseImage::Local(ref alias) => {
+                if alias.is_empty() || alias.contains(':') {
+                    return invalid(format!("{:?} is not a valid alias", alias));
+                }
+                alias
+            }
+        };
+
+        Ok(location.prefix(name))
+    }
+}
+
+impl<'a> From<&'a str> for BaseImage {
+    /// Parse an `lxc launch` image argument, where `remote:alias` is a remote
+    /// image and anything else is a local alias
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::BaseImage;
+    ///
+    /// assert_eq!(BaseImage::from("ubuntu:16.04"), BaseImage::Remote {
+    ///     remote: "ubuntu".to_string(),
+    ///     alias: "16.04".to_string(),
+    /// });
+    /// assert_eq!(BaseImage::from("my-image"), BaseImage::Local("my-image".to_string()));
+    /// ```
+    fn from(base: &'a str) -> Self {
+        match base.split_once(':') {
+            Some((remote, alias)) => BaseImage::Remote {
+                remote: remote.to_string(),
+                alias: alias.to_string(),
+            },
+            None => BaseImage::Local(base.to_string())
+        }
+    }
+}
+
+/// A device to add to a container before it first boots
+struct PendingDevice {
+    name: String,
+    kind: DeviceKind,
+    options: Vec<(String, String)>,
+}
+
+/// A builder for launching LXD containers
+pub struct ContainerBuilder {
+    location: Location,
+    name: String,
+    base: BaseImage,
+    privileged: bool,
+    ephemeral: bool,
+    resolv_conf: Option<String>,
+    raw_idmap: Option<String>,
+    dhclient: bool,
+    network: Option<String>,
+    storage: Option<String>,
+    profiles: Vec<String>,
+    config: Vec<(String, String)>,
+    devices: Vec<PendingDevice>,
+    wait_network: bool,
+}
+
+impl ContainerBuilder {
+    /// Create a builder for a new LXD container
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the container
+    /// * `base` - The image to create the container from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{BaseImage, ContainerBuilder, Location};
+    ///
+    /// let builder = ContainerBuilder::new(Location::Local, "test-builder", BaseImage::Fingerprint("8f7f9b4a".to_string()));
+    /// ```
+    pub fn new<B: Into<BaseImage>>(location: Location, name: &str, base: B) -> Self {
+        ContainerBuilder {
+            location,
+            name: name.to_string(),
+            base: base.into(),
+            privileged: false,
+            ephemeral: true,
+            resolv_conf: None,
+            raw_idmap: None,
+            dhclient: false,
+            network: Some("lxdbr0".to_string()),
+            storage: None,
+            profiles: Vec::new(),
+            config: Vec::new(),
+            devices: Vec::new(),
+            wait_network: true,
+        }
+    }
+
+    /// Make the container privileged
+    ///
+    /// # Safety
+    ///
+    /// A privileged container runs as root on the host and is not confined by
+    /// AppArmor, so anything running inside it can escape to the host
+    pub unsafe fn privileged(mut self) -> Self {
+        self.privileged = true;
+        self
+    }
+
+    /// Choose whether the container is ephemeral, which is the default
+    ///
+    /// An ephemeral container is deleted when it is stopped, and is stopped
+    /// when its handle is dropped. A persistent container is left running.
+    ///
+    /// # Arguments
+    ///
+    /// * `ephemeral` - True to delete the container when it is stopped
+    pub fn ephemeral(mut self, ephemeral: bool) -> Self {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    /// Replace `/etc/resolv.conf` in the container once it has launched
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The contents of the new `/etc/resolv.conf`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::builder(Location::Local, "test-resolv-conf", "ubuntu:16.04")
+    ///     .resolv_conf("nameserver 10.0.0.1\nsearch internal.example.com\n")
+    ///     .launch()
+    ///     .unwrap();
+    /// let resolv_conf = container.exec_output(&["cat", "/etc/resolv.conf"]).unwrap().stdout;
+    /// assert_eq!(resolv_conf, b"nameserver 10.0.0.1\nsearch internal.example.com\n");
+    /// ```
+    pub fn resolv_conf(mut self, contents: &str) -> Self {
+        self.resolv_conf = Some(contents.to_string());
+        self
+    }
+
+    /// Set `raw.idmap` on the container before it first boots
+    ///
+    /// The container is created stopped with `lxc init`, so that the map is
+    /// applied before the root filesystem is shifted, and is started afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `idmap` - The idmap, one `uid`, `gid` or `both` entry per line
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::builder(Location::Local, "test-raw-idmap", "ubuntu:16.04")
+    ///     .raw_idmap("uid 5000 1000\ngid 5000 1000")
+    ///     .launch()
+    ///     .unwrap();
+    /// ```
+    pub fn raw_idmap(mut self, idmap: &str) -> Self {
+        self.raw_idmap = Some(idmap.to_string());
+        self
+    }
+
+    /// Choose the network to attach the container to, which is `lxdbr0` by
+    /// default
+    ///
+    /// With no network, the container gets the network devices of its
+    /// profiles.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - The name of the network, or `None` to use the profiles
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::builder(Location::Local, "test-network", "ubuntu:16.04")
+    ///     .network(Some("br0"))
+    ///     .launch()
+    ///     .unwrap();
+    /// ```
+    pub fn network(mut self, network: Option<&str>) -> Self {
+        self.network = network.map(|network| network.to_string());
+        self
+    }
+
+    /// Create the root disk of the container in a storage pool, instead of
+    /// the pool of its profiles
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - The name of the storage pool
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::builder(Location::Local, "test-builder-storage", "ubuntu:16.04")
+    ///     .storage("nvme")
+    ///     .launch()
+    ///     .unwrap();
+    /// ```
+    pub fn storage(mut self, pool: &str) -> Self {
+        self.storage = Some(pool.to_string());
+        self
+    }
+
+    /// Apply a profile to the container, in the order they are added
+    ///
+    /// Choosing any profile replaces the `default` profile, so add it first
+    /// to keep it.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The name of the profile
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::builder(Location::Local, "test-builder-profile", "ubuntu:16.04")
+    ///     .profile("default")
+    ///     .profile("limits")
+    ///     .launch()
+    ///     .unwrap();
+    /// ```
+    pub fn profile(mut self, profile: &str) -> Self {
+        self.profiles.push(profile.to_string());
+        self
+    }
+
+    /// Set a config key on the container when it is created
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The config key, `limits.cpu` for example
+    /// * `value` - The value of the config key
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::builder(Location::Local, "test-builder-config", "ubuntu:16.04")
+    ///     .config("limits.cpu", "2")
+    ///     .config("limits.memory", "1GB")
+    ///     .launch()
+    ///     .unwrap();
+    /// ```
+    pub fn config(mut self, key: &str, value: &str) -> Self {
+        self.config.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add a device to the container before it first boots, see
+    /// [`Container::add_device`]
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the device
+    /// * `kind` - The type of the device
+    /// * `options` - The config keys and values of the device
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, DeviceKind, Location};
+    ///
+    /// let container = Container::builder(Location::Local, "test-builder-device", "ubuntu:16.04")
+    ///     .device("data", DeviceKind::Disk, &[("source", "/srv/data"), ("path", "/data")])
+    ///     .launch()
+    ///     .unwrap();
+    /// ```
+    pub fn device(mut self, name: &str, kind: DeviceKind, options: &[(&str, &str)]) -> Self {
+        let options = options.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+        self.devices.push(PendingDevice {
+            name: name.to_string(),
+            kind,
+            options,
+        });
+        self
+    }
+
+    /// Choose whether to wait for the container to get an address once it
+    /// has launched, which is the default
+    ///
+    /// # Arguments
+    ///
+    /// * `wait_network` - True to wait up to 30 seconds for an address
+    pub fn wait_network(mut self, wait_network: bool) -> Self {
+        self.wait_network = wait_network;
+        self
+    }
+
+    /// Run `dhclient` in the container once it has launched
+    ///
+    /// By default, launching waits for the container to get an address, but
+    /// does not fail if it does not get one. Images that configure their
+    /// network with `ifupdown` and `dhclient` may need this to get an address
+    /// at all. Launching fails if `dhclient` fails, or is not installed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dhclient` - True to run `dhclient` after launching
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::builder(Location::Local, "test-dhclient", "ubuntu:16.04")
+    ///     .dhclient(true)
+    ///     .launch()
+    ///     .unwrap();
+    /// ```
+    pub fn dhclient(mut self, dhclient: bool) -> Self {
+        self.dhclient = dhclient;
+        self
+    }
+
+    /// Apply the settings that have to be made before a created container
+    /// first boots
+    fn configure(container: &mut Container, raw_idmap: Option<&str>, devices: &[PendingDevice]) -> Result<()> {
+        if let Some(raw_idmap) = raw_idmap {
+            lxc_input(&container.args(&["config", "set", container.name(), "raw.idmap", "-"]), raw_idmap.as_bytes())?;
+        }
+        for device in devices.iter() {
+            let options: Vec<(&str, &str)> = device.options.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+            container.add_device(&device.name, device.kind, &options)?;
+        }
+        Ok(())
+    }
+
+    /// Launch the container
+    ///
+    /// # Return
+    ///
+    /// The newly created LXD container
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating container will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{BaseImage, ContainerBuilder, Location};
+    ///
+    /// let container = ContainerBuilder::new(Location::Local, "test-builder-launch", BaseImage::Remote {
+    ///     remote: "ubuntu".to_string(),
+    ///     alias: "16.04".to_string(),
+    /// }).launch().unwrap();
+    /// ```
+    pub fn launch(self) -> Result<Container> {
+        let source = self.base.source(&self.location)?;
+        // The handle does not own the container until it has been created,
+        // so that failing because the name is taken cannot stop and delete
+        // an existing container when the handle is dropped
+        let mut container = Container::from_name(self.location, &self.name, false);
+        container.ephemeral = self.ephemeral;
+
+        // raw.idmap can only be changed while stopped, and devices should be
+        // there when the container first boots, so create the container
+        // without starting it when either is needed
+        let init = self.raw_idmap.is_some() || !self.devices.is_empty();
+        let command = if init { "init" } else { "launch" };
+        let mut args = container.args(&[command, &source, container.name()]);
+        if let Some(ref network) = self.network {
+            args.extend_from_slice(&["-n", network]);
+        }
+        if let Some(ref pool) = self.storage {
+            args.extend_from_slice(&["-s", pool]);
+        }
+        if self.ephemeral {
+            args.push("-e");
+        }
+        for profile in self.profiles.iter() {
+            args.extend_from_slice(&["-p", profile]);
+        }
+        if self.privileged {
+            args.extend_from_slice(&[
+                "-c", "security.privileged=true",
+                "-c", "raw.lxc=lxc.apparmor.profile=unconfined"
+            ]);
+        }
+        let config: Vec<String> = self.config.iter().map(|(key, value)| {
+            format!("{}={}", key, value)
+        }).collect();
+        for arg in config.iter() {
+            args.extend_from_slice(&["-c", arg]);
+        }
+        lxc(&args)?;
+        container.owned = true;
+
+        if init {
+            let started = ContainerBuilder::configure(&mut container, self.raw_idmap.as_deref(), &self.devices).and_then(|()| {
+                lxc(&container.args(&["start", container.name()]))
+            });
+            if let Err(err) = started {
+                // Dropping the handle would only stop the container, which
+                // leaves a container that never started behind
+                container.owned = false;
+                let _ = lxc(&container.args(&["delete", "--force", container.name()]));
+                return Err(err);
+            }
+        }
+
+        // XXX: https://bugzilla.redhat.com/show_bug.cgi?id=1419315
+        lxc(&container.args(&["exec", container.name(), "--mode=non-interactive", "-n", "--", "touch", "/etc/fstab"]))?;
+
+        if self.dhclient {
+            // Hack to wait for network up and running
+            lxc(&container.args(&["exec", container.name(), "--mode=non-interactive", "-n", "--", "dhclient"]))?;
+        } else if self.wait_network {
+            // Most images get an address on their own, and a container
+            // without one is still usable
+            match container.wait_for_address(Duration::from_secs(30), Duration::from_millis(500)) {
+                Ok(_) | Err(Error::TimedOut(_)) => (),
+                Err(err) => return Err(err)
+            }
+        }
+
+        if let Some(resolv_conf) = self.resolv_conf {
+            // Replace rather than write through a symlink to a file managed
+            // by systemd-resolved
+            lxc_input(&container.args(&[
+                "exec", container.name(), "--mode=non-interactive", "--",
+                "sh", "-c", "rm -f /etc/resolv.conf && cat > /etc/resolv.conf"
+            ]), resolv_conf.as_bytes())?;
+        }
+
+        Ok(container)
+    }
+}
@@ -1,136 +1,2308 @@
-use std::io;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
+use std::os::unix::fs::{self as unix_fs, PermissionsExt};
 use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use super::{lxc, Location, Snapshot};
+use super::{batch, command_line, lxc, lxc_capture, lxc_capture_status, lxc_input, lxc_lines, lxc_output, lxc_progress, lxc_status, lxc_timeout, BaseImage, ContainerBuilder, DeviceKind, Error, ExitStatus, Info, IoLimits, Location, MemoryLimit, PushOptions, Result, Snapshot, Status, StoragePool};
+use super::info::State;
+use super::snap::check_readable;
+use super::units::format_bytes;
 
-/// An LXD ephemeral container
+/// An LXD container
 pub struct Container {
-    name: String
+    location: Location,
+    name: String,
+    pub(crate) owned: bool,
+    pub(crate) ephemeral: bool
 }
 
 impl Container {
-    /// Create a new LXD container
+    /// Create a new ephemeral LXD container
+    ///
+    /// The container is deleted when it is stopped, which happens when the
+    /// returned handle is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the container
+    /// * `base` - The base distribution to use, `ubuntu:16.04` for example
+    ///
+    /// # Return
+    ///
+    /// The newly created LXD container
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating container will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-new", "ubuntu:16.04").unwrap();
+    /// ```
+    pub fn new(location: Location, name: &str, base: &str) -> Result<Self> {
+        Container::create(location, name, base, true)
+    }
+
+    /// Create a new LXD container, choosing whether it is ephemeral
+    ///
+    /// A persistent container is left running when the returned handle is
+    /// dropped, and survives being stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the container
+    /// * `base` - The base distribution to use, `ubuntu:16.04` for example
+    /// * `ephemeral` - True to delete the container when it is stopped
+    ///
+    /// # Return
+    ///
+    /// The newly created LXD container
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating container will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::create(Location::Local, "test-create", "ubuntu:16.04", false).unwrap();
+    /// container.stop().unwrap();
+    /// container.start().unwrap();
+    /// ```
+    pub fn create(location: Location, name: &str, base: &str, ephemeral: bool) -> Result<Self> {
+        Container::builder(location, name, base).ephemeral(ephemeral).launch()
+    }
+
+    /// Create a new privileged LXD container
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the container
+    /// * `base` - The base distribution to use, `ubuntu:16.04` for example
+    ///
+    /// # Return
+    ///
+    /// The newly created LXD container
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating container will be returned
+    ///
+    /// # Safety
+    ///
+    /// A privileged container runs as root on the host and is not confined by
+    /// AppArmor, so anything running inside it can escape to the host
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = unsafe { Container::new_privileged(Location::Local, "test-new-privileged", "ubuntu:16.04").unwrap() };
+    /// ```
+    pub unsafe fn new_privileged(location: Location, name: &str, base: &str) -> Result<Self> {
+        Container::builder(location, name, base).privileged().launch()
+    }
+
+    /// Create many LXD containers from the same base, in parallel
+    ///
+    /// If any container fails to launch, the containers that did launch are
+    /// stopped and the first error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `base` - The image to create the containers from
+    /// * `names` - The names of the containers
+    /// * `parallelism` - The maximum number of containers to launch at once
+    ///
+    /// # Return
+    ///
+    /// The newly created LXD containers, in the same order as `names`
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating containers will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let containers = Container::launch_many(Location::Local, "ubuntu:16.04", &["test-worker-1", "test-worker-2"], 2).unwrap();
+    /// ```
+    pub fn launch_many<B: Into<BaseImage>>(location: Location, base: B, names: &[&str], parallelism: usize) -> Result<Vec<Self>> {
+        let base = base.into();
+        batch(names, parallelism, |name| {
+            Container::builder(location.clone(), name, base.clone()).launch()
+        }).into_iter().collect()
+    }
+
+    /// Create a builder for a new LXD container
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the container
+    /// * `base` - The image to create the container from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{BaseImage, Container, Location};
+    ///
+    /// let container = Container::builder(Location::Local, "test-builder", BaseImage::Local("my-image".to_string())).launch().unwrap();
+    /// ```
+    pub fn builder<B: Into<BaseImage>>(location: Location, name: &str, base: B) -> ContainerBuilder {
+        ContainerBuilder::new(location, name, base)
+    }
+
+    /// Check if a container exists, see [`Info::exists`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-container-exists", "ubuntu:16.04").unwrap();
+    /// assert!(Container::exists(Location::Local, "test-container-exists").unwrap());
+    /// ```
+    pub fn exists(location: Location, name: &str) -> Result<bool> {
+        Info::exists(location, name)
+    }
+
+    /// Attach to an existing LXD container
+    ///
+    /// The returned handle does not own the container, so it is left running
+    /// when the handle is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the container
+    ///
+    /// # Return
+    ///
+    /// A handle to the existing LXD container
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while looking up the container will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-attach", "ubuntu:16.04").unwrap();
+    /// let attached = Container::attach(Location::Local, "test-attach").unwrap();
+    /// ```
+    pub fn attach(location: Location, name: &str) -> Result<Self> {
+        let info = Info::new(location.clone(), name)?;
+        let mut container = Container::from_name(location, name, false);
+        container.ephemeral = info.ephemeral;
+        Ok(container)
+    }
+
+    /// Attach to a container that is being migrated to a host
+    ///
+    /// This waits for the container to appear on the destination, polling
+    /// once a second, so it can be used right after starting a migration with
+    /// an external tool. Like [`Container::attach`], the returned handle does
+    /// not own the container.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the destination host
+    /// * `name` - The name of the container on the destination
+    /// * `timeout` - How long to wait for the container to appear
+    ///
+    /// # Return
+    ///
+    /// A handle to the migrated LXD container
+    ///
+    /// # Errors
+    ///
+    /// Errors will be returned if the container does not appear before the
+    /// timeout, or if looking it up fails
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    /// use std::time::Duration;
+    ///
+    /// let container = Container::from_migration(Location::Remote("production".to_string()), "test-migration", Duration::from_secs(10)).unwrap();
+    /// ```
+    pub fn from_migration(location: Location, name: &str, timeout: Duration) -> Result<Self> {
+        let start = Instant::now();
+        loop {
+            match Container::attach(location.clone(), name) {
+                Ok(container) => return Ok(container),
+                Err(Error::NotFound(_)) if start.elapsed() < timeout => (),
+                Err(err) => return Err(err)
+            }
+
+            thread::sleep(Duration::from_secs(1));
+        }
+    }
+
+    /// Create a handle to a persistent container without launching it
+    pub(crate) fn from_name(location: Location, name: &str, owned: bool) -> Self {
+        let full_name = location.prefix(name);
+
+        Container {
+            location,
+            name: full_name,
+            owned,
+            ephemeral: false
+        }
+    }
+
+    /// Get full name of container
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the `lxc` arguments that run a command in the project of the
+    /// container
+    pub(crate) fn args<'a>(&'a self, args: &[&'a str]) -> Vec<&'a str> {
+        self.location.args(args)
+    }
+
+    /// Get name of container without the remote prefix
+    fn short_name(&self) -> &str {
+        match self.location.remote() {
+            None => &self.name,
+            Some(remote) => &self.name[remote.len() + 1..]
+        }
+    }
+
+    /// Retrieve LXD container information for this container
+    ///
+    /// # Return
+    ///
+    /// The LXD container information
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-container-info", "ubuntu:16.04").unwrap();
+    /// let info = container.info().unwrap();
+    /// assert_eq!(info.name, "test-container-info");
+    /// ```
+    pub fn info(&self) -> Result<Info> {
+        Info::new(self.location.clone(), self.short_name())
+    }
+
+    /// Get the status of this container
+    ///
+    /// Status codes not known to this library are returned as `Status::Other`,
+    /// with the status that LXD reported.
+    ///
+    /// # Return
+    ///
+    /// The status of the container
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Status};
+    ///
+    /// let container = Container::new(Location::Local, "test-container-status", "ubuntu:16.04").unwrap();
+    /// assert_eq!(container.status().unwrap(), Status::Running);
+    /// ```
+    pub fn status(&self) -> Result<Status> {
+        let info = self.info()?;
+        Ok(match info.status_enum() {
+            Status::Unknown(_) => Status::Other(info.status),
+            status => status
+        })
+    }
+
+    /// Wait for this container to be running
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait before giving up
+    /// * `interval` - How long to wait between checks of the status
+    ///
+    /// # Return
+    ///
+    /// An empty tuple once the container is running
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned, or
+    /// `Error::TimedOut` if the container is not running before the timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    /// use std::time::Duration;
+    ///
+    /// let container = Container::new(Location::Local, "test-wait-running", "ubuntu:16.04").unwrap();
+    /// container.wait_running(Duration::from_secs(30), Duration::from_millis(500)).unwrap();
+    /// ```
+    pub fn wait_running(&self, timeout: Duration, interval: Duration) -> Result<()> {
+        let start = Instant::now();
+        loop {
+            let status = self.status()?;
+            if status == Status::Running {
+                return Ok(());
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(Error::TimedOut(
+                    format!("LXD container: {} was still {:?} after {:?}", self.name, status, timeout)
+                ));
+            }
+
+            thread::sleep(interval);
+        }
+    }
+
+    /// Get the number of bytes used by the root disk of the container
+    ///
+    /// # Return
+    ///
+    /// The disk usage of the root volume in bytes
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving state will be returned. If
+    /// the storage driver does not report usage, `Error::Unsupported` is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-disk-usage", "ubuntu:16.04").unwrap();
+    /// let bytes = container.disk_usage().unwrap();
+    /// ```
+    pub fn disk_usage(&self) -> Result<u64> {
+        let path = format!("/1.0/instances/{}/state", self.short_name());
+        let json = lxc_output(&self.args(&["query", &self.location.prefix(&path)]))?;
+
+        let state = serde_json::from_slice::<State>(&json).map_err(Error::Json)?;
+
+        state.disk.get("root").map(|root| root.usage).ok_or_else(|| {
+            Error::Unsupported(
+                format!("LXD container: {} does not report root disk usage", self.name)
+            )
+        })
+    }
+
+    /// Check if the container is ephemeral
+    ///
+    /// # Return
+    ///
+    /// True if the container will be deleted when it is stopped
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-is-ephemeral", "ubuntu:16.04").unwrap();
+    /// assert!(container.is_ephemeral().unwrap());
+    /// ```
+    pub fn is_ephemeral(&self) -> Result<bool> {
+        Ok(self.info()?.ephemeral)
+    }
+
+    /// Change whether the container is ephemeral
+    ///
+    /// Turning this off keeps the container around after it is stopped. LXD
+    /// may refuse to change it while the container is running, in which case
+    /// the container has to be stopped first.
+    ///
+    /// # Arguments
+    ///
+    /// * `on` - True to delete the container when it is stopped
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while changing the setting will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-set-ephemeral", "ubuntu:16.04").unwrap();
+    /// container.set_ephemeral(false).unwrap();
+    /// ```
+    pub fn set_ephemeral(&mut self, on: bool) -> Result<()> {
+        let info = self.info()?;
+        if info.ephemeral == on {
+            self.ephemeral = on;
+            return Ok(());
+        }
+
+        lxc(&self.args(&["config", "set", &self.name, &format!("ephemeral={}", on), "--property"])).map_err(|err| {
+            if info.status == "Stopped" {
+                err
+            } else {
+                Error::Other(
+                    format!("LXD container: {} must be stopped to change ephemeral: {}", self.name, err)
+                )
+            }
+        })?;
+
+        self.ephemeral = on;
+        Ok(())
+    }
+
+    /// Get the IP addresses of the container
+    ///
+    /// Loopback and link-local addresses are left out. Filter the result with
+    /// [`IpAddr::is_ipv4`] or [`IpAddr::is_ipv6`] to choose a family.
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-addresses", "ubuntu:16.04").unwrap();
+    /// let ipv4 = container.addresses().unwrap().into_iter().find(|address| address.is_ipv4()).unwrap();
+    /// ```
+    pub fn addresses(&self) -> Result<Vec<IpAddr>> {
+        Ok(self.info()?.addresses())
+    }
+
+    /// Wait for the container to have an IP address
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait before giving up
+    /// * `interval` - How long to wait between checks of the addresses
+    ///
+    /// # Return
+    ///
+    /// The IP addresses of the container, as returned by [`Container::addresses`]
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned, or
+    /// `Error::TimedOut` if the container has no address before the timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    /// use std::time::Duration;
+    ///
+    /// let container = Container::new(Location::Local, "test-wait-for-address", "ubuntu:16.04").unwrap();
+    /// let addresses = container.wait_for_address(Duration::from_secs(30), Duration::from_millis(500)).unwrap();
+    /// assert!(!addresses.is_empty());
+    /// ```
+    pub fn wait_for_address(&self, timeout: Duration, interval: Duration) -> Result<Vec<IpAddr>> {
+        let start = Instant::now();
+        loop {
+            let addresses = self.addresses()?;
+            if !addresses.is_empty() {
+                return Ok(addresses);
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(Error::TimedOut(
+                    format!("LXD container: {} had no address after {:?}", self.name, timeout)
+                ));
+            }
+
+            thread::sleep(interval);
+        }
+    }
+
+    /// Check if nesting is enabled, allowing containers inside the container
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-is-nested", "ubuntu:16.04").unwrap();
+    /// assert!(!container.is_nested().unwrap());
+    /// ```
+    pub fn is_nested(&self) -> Result<bool> {
+        Ok(self.info()?.is_nested())
+    }
+
+    /// Check if the container is privileged
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = unsafe { Container::new_privileged(Location::Local, "test-is-privileged", "ubuntu:16.04").unwrap() };
+    /// assert!(container.is_privileged().unwrap());
+    /// ```
+    pub fn is_privileged(&self) -> Result<bool> {
+        Ok(self.info()?.is_privileged())
+    }
+
+    /// Get a config key of the container
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The config key, `limits.cpu` for example
+    ///
+    /// # Return
+    ///
+    /// The value of the key, or `None` if it is not set
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while reading config will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-get-config", "ubuntu:16.04").unwrap();
+    /// assert_eq!(container.get_config("security.nesting").unwrap(), None);
+    /// ```
+    pub fn get_config(&self, key: &str) -> Result<Option<String>> {
+        let output = lxc_output(&self.args(&["config", "get", &self.name, key]))?;
+        let value = String::from_utf8_lossy(&output);
+        // lxc prints an empty line for keys that are not set
+        let value = value.strip_suffix('\n').unwrap_or(&value);
+        if value.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(value.to_string()))
+        }
+    }
+
+    /// Set a config key on the container
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The config key, `limits.cpu` for example
+    /// * `value` - The new value
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while changing config will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-set-config", "ubuntu:16.04").unwrap();
+    /// container.set_config("boot.autostart", "true").unwrap();
+    /// assert_eq!(container.get_config("boot.autostart").unwrap(), Some("true".to_string()));
+    /// ```
+    pub fn set_config(&mut self, key: &str, value: &str) -> Result<()> {
+        lxc(&self.args(&["config", "set", &self.name, key, value]))
+    }
+
+    /// Set a config key on the container to a value that may span multiple lines
+    fn set_config_multiline(&mut self, key: &str, value: &str) -> Result<()> {
+        // Passing the value on stdin keeps newlines intact
+        lxc_input(&self.args(&["config", "set", &self.name, key, "-"]), value.as_bytes())
+    }
+
+    /// Set raw LXC configuration for the container
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The value of `raw.lxc`, one LXC config line per line
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while setting config will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-raw-lxc", "ubuntu:16.04").unwrap();
+    /// container.set_raw_lxc("lxc.apparmor.profile=unconfined\nlxc.cgroup.devices.allow=a").unwrap();
+    /// ```
+    pub fn set_raw_lxc(&mut self, config: &str) -> Result<()> {
+        self.set_config_multiline("raw.lxc", config)
+    }
+
+    /// Set a raw idmap for the container
+    ///
+    /// The container must be restarted for the new mapping to take effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `mapping` - The value of `raw.idmap`, one mapping per line
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while setting config will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-raw-idmap", "ubuntu:16.04").unwrap();
+    /// container.set_raw_idmap("uid 1000 1000\ngid 1000 1000").unwrap();
+    /// ```
+    pub fn set_raw_idmap(&mut self, mapping: &str) -> Result<()> {
+        self.set_config_multiline("raw.idmap", mapping)
+    }
+
+    /// Set the autostart order and delay of the container
+    ///
+    /// Autostarted containers are started from the highest order to the
+    /// lowest, waiting `delay` after each one before starting the next.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The value of `boot.autostart.order`
+    /// * `delay` - The value of `boot.autostart.delay`, rounded down to seconds
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while setting config will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    /// use std::time::Duration;
+    ///
+    /// let mut container = Container::new(Location::Local, "test-autostart-order", "ubuntu:16.04").unwrap();
+    /// container.set_autostart_order(10, Duration::from_secs(5)).unwrap();
+    /// ```
+    pub fn set_autostart_order(&mut self, order: i32, delay: Duration) -> Result<()> {
+        self.set_config("boot.autostart.order", &order.to_string())?;
+        self.set_config("boot.autostart.delay", &delay.as_secs().to_string())
+    }
+
+    /// Get the autostart order of the container
+    ///
+    /// # Return
+    ///
+    /// The value of `boot.autostart.order`, or `None` if it is not set
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    /// use std::time::Duration;
+    ///
+    /// let mut container = Container::new(Location::Local, "test-boot-order", "ubuntu:16.04").unwrap();
+    /// container.set_autostart_order(10, Duration::from_secs(5)).unwrap();
+    /// assert_eq!(container.boot_order().unwrap(), Some(10));
+    /// ```
+    pub fn boot_order(&self) -> Result<Option<i32>> {
+        Ok(self.info()?.boot_order())
+    }
+
+    /// Set the autostart order of a group of containers
+    ///
+    /// The containers are started in the order they are given, so the first
+    /// container receives the highest `boot.autostart.order`.
+    ///
+    /// # Arguments
+    ///
+    /// * `containers` - The containers, in the order they should start
+    /// * `delay` - How long to wait after starting each container
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while setting config will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    /// use std::time::Duration;
+    ///
+    /// let mut database = Container::new(Location::Local, "test-autostart-database", "ubuntu:16.04").unwrap();
+    /// let mut app = Container::new(Location::Local, "test-autostart-app", "ubuntu:16.04").unwrap();
+    /// Container::set_autostart_sequence(&mut [&mut database, &mut app], Duration::from_secs(10)).unwrap();
+    /// ```
+    pub fn set_autostart_sequence(containers: &mut [&mut Container], delay: Duration) -> Result<()> {
+        let count = containers.len();
+        for (i, container) in containers.iter_mut().enumerate() {
+            container.set_autostart_order((count - i) as i32, delay)?;
+        }
+        Ok(())
+    }
+
+    /// Copy the container to a remote host
+    ///
+    /// The copy is created stopped. The returned handle does not own the
+    /// copy, so it is left alone when the handle is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `remote` - The name of the remote to copy to
+    /// * `new_name` - The name of the copy
+    ///
+    /// # Return
+    ///
+    /// A handle to the copy on the remote host
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while copying will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-copy-to-remote", "ubuntu:16.04").unwrap();
+    /// let copy = container.copy_to_remote("production", "test-copy-to-remote").unwrap();
+    /// ```
+    pub fn copy_to_remote(&self, remote: &str, new_name: &str) -> Result<Container> {
+        self.copy(Location::Remote(remote.to_string()), new_name, false)
+    }
+
+    /// Copy the container
+    ///
+    /// The copy is created stopped. The returned handle does not own the
+    /// copy, so it is left alone when the handle is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host to copy to
+    /// * `new_name` - The name of the copy
+    /// * `ephemeral` - True to make the copy ephemeral
+    ///
+    /// # Return
+    ///
+    /// A handle to the copy
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while copying will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-copy", "ubuntu:16.04").unwrap();
+    /// let mut copy = container.copy(Location::Local, "test-copy-2", false).unwrap();
+    /// copy.start().unwrap();
+    /// ```
+    pub fn copy(&self, location: Location, new_name: &str, ephemeral: bool) -> Result<Container> {
+        let mut copy = Container::from_name(location, new_name, false);
+        copy.ephemeral = ephemeral;
+
+        let mut args = self.args(&["copy", self.name.as_str(), copy.name.as_str()]);
+        if ephemeral {
+            args.push("--ephemeral");
+        }
+        args.extend(self.location.target_args(&copy.location));
+        lxc(&args)?;
+
+        Ok(copy)
+    }
+
+    /// Move the container to another host, or rename it on the same host
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - The location of the host to move to
+    /// * `new_name` - The name on the destination, or `None` to keep the name
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while moving will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::attach(Location::Local, "test-move-to").unwrap();
+    /// container.move_to(Location::Remote("production".to_string()), None).unwrap();
+    /// assert_eq!(container.name(), "production:test-move-to");
+    /// ```
+    pub fn move_to(&mut self, dest: Location, new_name: Option<&str>) -> Result<()> {
+        self.move_to_with_progress(dest, new_name, |_| ())
+    }
+
+    /// Move the container to another host, reporting progress
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - The location of the host to move to
+    /// * `new_name` - The name on the destination, or `None` to keep the name
+    /// * `progress` - Called with the percentage complete as the move proceeds
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while moving will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::attach(Location::Local, "test-move-to-progress").unwrap();
+    /// container.move_to_with_progress(Location::Remote("production".to_string()), None, |percent| {
+    ///     println!("{}%", percent);
+    /// }).unwrap();
+    /// ```
+    pub fn move_to_with_progress<F: FnMut(u8)>(&mut self, dest: Location, new_name: Option<&str>, progress: F) -> Result<()> {
+        let new_name = new_name.unwrap_or_else(|| self.short_name()).to_string();
+        let moved = Container::from_name(dest, &new_name, false);
+        let mut args = self.args(&["move", &self.name, &moved.name]);
+        args.extend(self.location.target_args(&moved.location));
+        lxc_progress(&args, progress)?;
+
+        self.location = moved.location.clone();
+        self.name = moved.name.clone();
+        Ok(())
+    }
+
+    /// Try to bring a container in an error state back to running
+    ///
+    /// A container that is not running is force stopped and then started
+    /// again. Ephemeral containers are deleted when stopped, so they cannot be
+    /// recovered this way.
+    ///
+    /// # Return
+    ///
+    /// The status of the container after recovery
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while restarting will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Status};
+    ///
+    /// let mut container = Container::attach(Location::Local, "test-recover").unwrap();
+    /// assert_eq!(container.recover().unwrap(), Status::Running);
+    /// ```
+    pub fn recover(&mut self) -> Result<Status> {
+        let status = self.info()?.status_enum();
+        if status == Status::Running {
+            return Ok(status);
+        }
+
+        if status != Status::Stopped {
+            // Force stopping a wedged container may report an error even
+            // though it has stopped, so check the result by starting it
+            let _ = lxc(&self.args(&["stop", "--force", &self.name]));
+        }
+        lxc(&self.args(&["start", &self.name]))?;
+
+        Ok(self.info()?.status_enum())
+    }
+
+    /// Replace the root filesystem of the container with a new image
+    ///
+    /// The config, devices and snapshots of the container are kept. The
+    /// container must be stopped, so an ephemeral container has to be made
+    /// persistent with [`Container::set_ephemeral`] before it can be rebuilt.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The image to rebuild the container from
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while rebuilding will be returned, or an
+    /// error if the container is not stopped
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::attach(Location::Local, "test-rebuild").unwrap();
+    /// container.rebuild("ubuntu:18.04").unwrap();
+    /// ```
+    pub fn rebuild<B: Into<BaseImage>>(&mut self, base: B) -> Result<()> {
+        let source = base.into().source(&self.location)?;
+
+        let status = self.info()?.status_enum();
+        if status != Status::Stopped {
+            return Err(Error::Other(
+                format!("LXD container: {} must be stopped to rebuild, but is {:?}", self.name, status)
+            ));
+        }
+
+        lxc(&self.args(&["rebuild", &source, &self.name]))
+    }
+
+    /// Start the container
+    ///
+    /// Ephemeral containers are deleted when stopped, so only persistent
+    /// containers can be started again.
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while starting will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::create(Location::Local, "test-start", "ubuntu:16.04", false).unwrap();
+    /// container.stop().unwrap();
+    /// container.start().unwrap();
+    /// ```
+    pub fn start(&mut self) -> Result<()> {
+        lxc(&self.args(&["start", &self.name]))
+    }
+
+    /// Stop the container
+    ///
+    /// A container that is already stopped is left alone, as is an
+    /// ephemeral container that has already been deleted.
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while stopping will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::create(Location::Local, "test-stop", "ubuntu:16.04", false).unwrap();
+    /// container.stop().unwrap();
+    /// container.stop().unwrap();
+    /// ```
+    pub fn stop(&mut self) -> Result<()> {
+        match self.info() {
+            Ok(ref info) if info.status_enum() == Status::Stopped => return Ok(()),
+            Err(Error::NotFound(_)) if self.ephemeral => return Ok(()),
+            _ => ()
+        }
+
+        lxc(&self.args(&["stop", &self.name]))
+    }
+
+    /// Restart the container
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for the container to shut down cleanly,
+    ///   or `None` to use the LXD default
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while restarting will be returned, which
+    /// includes the container not shutting down before the timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    /// use std::time::Duration;
+    ///
+    /// let mut container = Container::new(Location::Local, "test-restart", "ubuntu:16.04").unwrap();
+    /// container.restart(Some(Duration::from_secs(30))).unwrap();
+    /// ```
+    pub fn restart(&mut self, timeout: Option<Duration>) -> Result<()> {
+        match timeout {
+            Some(timeout) => {
+                let secs = timeout.as_secs().max(1).to_string();
+                lxc(&self.args(&["restart", &self.name, "--timeout", &secs]))
+            },
+            None => lxc(&self.args(&["restart", &self.name]))
+        }
+    }
+
+    /// Restart the container without waiting for it to shut down cleanly
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while restarting will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-force-restart", "ubuntu:16.04").unwrap();
+    /// container.force_restart().unwrap();
+    /// ```
+    pub fn force_restart(&mut self) -> Result<()> {
+        lxc(&self.args(&["restart", &self.name, "--force"]))
+    }
+
+    /// Rename the container
+    ///
+    /// LXD can only rename a stopped container, so an ephemeral container has
+    /// to be made persistent with [`Container::set_ephemeral`] first.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_name` - The new name of the container, without a remote
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while renaming will be returned, or an
+    /// error if the container is not stopped
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::create(Location::Local, "test-rename", "ubuntu:16.04", false).unwrap();
+    /// container.stop().unwrap();
+    /// container.rename("test-renamed").unwrap();
+    /// assert_eq!(container.name(), "test-renamed");
+    /// ```
+    pub fn rename(&mut self, new_name: &str) -> Result<()> {
+        let status = self.info()?.status_enum();
+        if status != Status::Stopped {
+            return Err(Error::Other(
+                format!("LXD container: {} must be stopped to rename, but is {:?}", self.name, status)
+            ));
+        }
+
+        let renamed = Container::from_name(self.location.clone(), new_name, false);
+        lxc(&self.args(&["rename", &self.name, &renamed.name]))?;
+
+        self.name = renamed.name.clone();
+        Ok(())
+    }
+
+    /// Delete the container, consuming the handle
+    ///
+    /// # Arguments
+    ///
+    /// * `force` - True to delete the container even if it is running
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while deleting will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::create(Location::Local, "test-delete", "ubuntu:16.04", false).unwrap();
+    /// container.delete(true).unwrap();
+    /// ```
+    pub fn delete(mut self, force: bool) -> Result<()> {
+        // Nothing is left to stop when the handle is dropped
+        self.owned = false;
+
+        let mut args = self.args(&["delete", self.name.as_str()]);
+        if force {
+            args.push("--force");
+        }
+        lxc(&args)
+    }
+
+    /// Freeze all processes in the container
+    ///
+    /// This maps to `lxc pause`. The container keeps its state and reports a
+    /// status of `Frozen` until it is unfrozen.
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while freezing will be returned, or an
+    /// error if the container is not running
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Info, Location, Status};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-freeze", "ubuntu:16.04").unwrap();
+    /// container.freeze().unwrap();
+    /// assert_eq!(Info::new(Location::Local, "test-freeze").unwrap().status_enum(), Status::Frozen);
+    /// container.unfreeze().unwrap();
+    /// ```
+    pub fn freeze(&mut self) -> Result<()> {
+        let status = self.info()?.status_enum();
+        if status != Status::Running {
+            return Err(Error::Other(
+                format!("LXD container: {} must be running to freeze, but is {:?}", self.name, status)
+            ));
+        }
+
+        lxc(&self.args(&["pause", &self.name]))
+    }
+
+    /// Pause the container, an alias of [`Container::freeze`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-pause", "ubuntu:16.04").unwrap();
+    /// container.pause().unwrap();
+    /// container.resume().unwrap();
+    /// ```
+    pub fn pause(&mut self) -> Result<()> {
+        self.freeze()
+    }
+
+    /// Unfreeze all processes in a frozen container
+    ///
+    /// This maps to `lxc start`, which resumes a frozen container.
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while unfreezing will be returned, or an
+    /// error if the container is not frozen
+    pub fn unfreeze(&mut self) -> Result<()> {
+        // lxc start would otherwise boot a stopped container
+        let status = self.info()?.status_enum();
+        if status != Status::Frozen {
+            return Err(Error::Other(
+                format!("LXD container: {} must be frozen to unfreeze, but is {:?}", self.name, status)
+            ));
+        }
+
+        lxc(&self.args(&["start", &self.name]))
+    }
+
+    /// Resume a paused container, an alias of [`Container::unfreeze`]
+    pub fn resume(&mut self) -> Result<()> {
+        self.unfreeze()
+    }
+
+    /// Publish the container as an image
+    ///
+    /// A stopped container is published directly. A running container is
+    /// snapshotted first and the snapshot is published, so that it does not
+    /// have to be stopped; the snapshot is deleted afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - alias of the new image
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while publishing will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-container-publish", "ubuntu:16.04").unwrap();
+    /// container.publish("test-container-publish").unwrap();
+    /// ```
+    pub fn publish(&self, alias: &str) -> Result<()> {
+        if self.status()? == Status::Stopped {
+            lxc(&self.args(&["publish", &self.name, "--alias", alias]))
+        } else {
+            // A unique name, so that a snapshot of the user's is never
+            // replaced, or deleted when this one is dropped
+            let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            let name = format!("publish-{}-{}", process::id(), since_epoch.as_nanos());
+            Snapshot::new(self, &name)?.publish(alias)
+        }
+    }
+
+    /// Create a snapshot of a container
+    ///
+    /// The snapshot is deleted when the returned handle is dropped, see
+    /// [`Container::snapshot_keep`] for a snapshot that persists.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the new snapshot
+    ///
+    /// # Return
+    ///
+    /// A new snapshot on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating snapshot will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Snapshot};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot", "ubuntu:16.04").unwrap();
+    /// container.snapshot("test-snapshot").unwrap();
+    /// ```
+    pub fn snapshot<'a>(&'a self, name: &str) -> Result<Snapshot<'a>> {
+        Snapshot::new(self, name)
+    }
+
+    /// Create a snapshot of a container, replacing any snapshot with the same name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the new snapshot
+    ///
+    /// # Return
+    ///
+    /// A new snapshot on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating snapshot will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-reuse", "ubuntu:16.04").unwrap();
+    /// container.snapshot_reuse("latest").unwrap();
+    /// ```
+    pub fn snapshot_reuse<'a>(&'a self, name: &str) -> Result<Snapshot<'a>> {
+        Snapshot::new_reuse(self, name)
+    }
+
+    /// Create a snapshot of a running container, including its memory state
+    ///
+    /// Like [`Container::snapshot`], the snapshot is deleted when the returned
+    /// handle is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the new snapshot
+    ///
+    /// # Return
+    ///
+    /// A new snapshot on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating snapshot will be returned,
+    /// with `Error::Unsupported` if CRIU is not available
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-stateful", "ubuntu:16.04").unwrap();
+    /// container.snapshot_stateful("running").unwrap();
+    /// ```
+    pub fn snapshot_stateful<'a>(&'a self, name: &str) -> Result<Snapshot<'a>> {
+        Snapshot::new_stateful(self, name)
+    }
+
+    /// Create a snapshot of a container that is kept until it is deleted
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the new snapshot
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating snapshot will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-keep", "ubuntu:16.04").unwrap();
+    /// container.snapshot_keep("backup").unwrap();
+    /// ```
+    pub fn snapshot_keep(&self, name: &str) -> Result<()> {
+        Snapshot::new(self, name)?.keep();
+        Ok(())
+    }
+
+    /// Run a command in an LXD container
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - An array of command arguments
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while executing will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-exec", "ubuntu:16.04").unwrap();
+    /// container.exec(&["echo", "hello"]).unwrap();
+    /// ```
+    pub fn exec(&mut self, command: &[&str]) -> Result<()> {
+        lxc(&self.exec_args(command))
+    }
+
+    /// Run a command in an LXD container, stopping it if it runs for too long
+    ///
+    /// When the timeout is reached, the host-side `lxc exec` process is sent
+    /// SIGTERM, which it forwards to the command in the container. If it has
+    /// not exited five seconds later, it is killed with SIGKILL, which is not
+    /// forwarded, so a command that ignores SIGTERM, or a process it forked,
+    /// may keep running in the container after this returns.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - An array of command arguments
+    /// * `timeout` - How long to let the command run
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while executing will be returned, with
+    /// `Error::TimedOut` if the command did not finish before the timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Error, Location};
+    /// use std::time::Duration;
+    ///
+    /// let mut container = Container::new(Location::Local, "test-exec-timeout", "ubuntu:16.04").unwrap();
+    /// container.exec_timeout(&["sleep", "1"], Duration::from_secs(10)).unwrap();
+    /// match container.exec_timeout(&["sleep", "60"], Duration::from_secs(1)) {
+    ///     Err(Error::TimedOut(_)) => (),
+    ///     other => panic!("expected a timeout, got {:?}", other),
+    /// }
+    /// ```
+    pub fn exec_timeout(&mut self, command: &[&str], timeout: Duration) -> Result<()> {
+        let args = self.exec_args(command);
+        match lxc_timeout(&args, timeout)? {
+            Some(status) if status.success() => Ok(()),
+            Some(status) => Err(Error::command_failed(&args, status, &[])),
+            None => Err(Error::TimedOut(
+                format!("LXD container: {} {:?} did not finish in {:?}", self.name, command, timeout)
+            ))
+        }
+    }
+
+    /// Run a command in an LXD container, writing data to its stdin
+    ///
+    /// Stdin is closed once all of the data is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - An array of command arguments
+    /// * `input` - The data to write to the stdin of the command
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while executing or writing stdin will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-exec-stdin", "ubuntu:16.04").unwrap();
+    /// container.exec_stdin(&["tee", "/etc/motd"], b"Welcome\n").unwrap();
+    /// container.exec_stdin(&["sh", "-s"], b"echo hello\n").unwrap();
+    /// ```
+    pub fn exec_stdin(&mut self, command: &[&str], input: &[u8]) -> Result<()> {
+        lxc_input(&self.exec_args(command), input)
+    }
+
+    /// Run a command in an LXD container with environment variables set
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - An array of command arguments
+    /// * `env` - The names and values of the environment variables
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while executing will be returned, or an
+    /// error if a variable name is empty or contains `=`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-exec-with-env", "ubuntu:16.04").unwrap();
+    /// container.exec_with_env(&["apt-get", "install", "-y", "tzdata"], &[("DEBIAN_FRONTEND", "noninteractive")]).unwrap();
+    /// ```
+    pub fn exec_with_env(&mut self, command: &[&str], env: &[(&str, &str)]) -> Result<()> {
+        let mut vars = Vec::new();
+        for &(key, value) in env.iter() {
+            if key.is_empty() || key.contains('=') {
+                return Err(Error::InvalidInput(
+                    format!("LXD container: {:?} is not a valid environment variable name", key)
+                ));
+            }
+            vars.push(format!("{}={}", key, value));
+        }
+
+        let mut flags = Vec::new();
+        for var in vars.iter() {
+            flags.extend_from_slice(&["--env", var.as_str()]);
+        }
+        lxc(&self.exec_args_with(&flags, command))
+    }
+
+    /// Run a command in an LXD container from a working directory
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory in the container to run the command in
+    /// * `command` - An array of command arguments
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while executing will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-exec-in-dir", "ubuntu:16.04").unwrap();
+    /// container.exec_in_dir("/tmp", &["touch", "file"]).unwrap();
+    /// ```
+    pub fn exec_in_dir(&mut self, dir: &str, command: &[&str]) -> Result<()> {
+        lxc(&self.exec_args_with(&["--cwd", dir], command))
+    }
+
+    /// Run a command in an LXD container as a user and group
+    ///
+    /// The `--user` and `--group` flags of `lxc exec` require LXD 2.15 or
+    /// later. The environment is not changed, so `HOME` is still that of root.
+    ///
+    /// # Arguments
+    ///
+    /// * `uid` - The user ID in the container to run the command as
+    /// * `gid` - The group ID in the container to run the command as
+    /// * `command` - An array of command arguments
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while executing will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-exec-as", "ubuntu:16.04").unwrap();
+    /// container.exec_as(1000, 1000, &["touch", "/tmp/file"]).unwrap();
+    /// ```
+    pub fn exec_as(&mut self, uid: u32, gid: u32, command: &[&str]) -> Result<()> {
+        let uid = uid.to_string();
+        let gid = gid.to_string();
+        lxc(&self.exec_args_with(&["--user", &uid, "--group", &gid], command))
+    }
+
+    /// Run a command in an LXD container and return how it finished
+    ///
+    /// Unlike [`Container::exec`], a command that fails is not an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - An array of command arguments
+    ///
+    /// # Return
+    ///
+    /// The exit code of the command, or the signal that killed it
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while starting the command will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, ExitStatus, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-exec-status", "ubuntu:16.04").unwrap();
+    /// assert_eq!(container.exec_status(&["false"]).unwrap(), ExitStatus::Exited(1));
+    /// assert_eq!(container.exec_status(&["sh", "-c", "kill -9 $$"]).unwrap().signal(), Some(9));
+    /// ```
+    pub fn exec_status(&mut self, command: &[&str]) -> Result<ExitStatus> {
+        lxc_status(&self.exec_args(command)).map(ExitStatus::from)
+    }
+
+    /// Run a command in an LXD container, passing each line of its output to
+    /// a callback as it is written
+    ///
+    /// Unlike [`Container::exec`], a command that fails is not an error, so
+    /// check the returned status.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - An array of command arguments
+    /// * `on_line` - Called with each line written to stdout, without the line ending
+    ///
+    /// # Return
+    ///
+    /// The exit code of the command, or the signal that killed it
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while starting the command or reading its
+    /// output will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-exec-streaming", "ubuntu:16.04").unwrap();
+    /// let mut lines = Vec::new();
+    /// let status = container.exec_streaming(&["sh", "-c", "echo hello; echo world"], |line| {
+    ///     lines.push(line.to_string());
+    /// }).unwrap();
+    /// assert!(status.success());
+    /// assert_eq!(lines, ["hello", "world"]);
+    /// ```
+    pub fn exec_streaming<F: FnMut(&str)>(&mut self, command: &[&str], mut on_line: F) -> Result<ExitStatus> {
+        lxc_lines(&self.exec_args(command), |line| {
+            on_line(line);
+            true
+        }).map(ExitStatus::from)
+    }
+
+    /// Get the `lxc` arguments that run a command in the container
+    fn exec_args<'a>(&'a self, command: &[&'a str]) -> Vec<&'a str> {
+        self.exec_args_with(&[], command)
+    }
+
+    /// Get the `lxc` arguments that run a command in the container, with
+    /// extra flags for `lxc exec`
+    fn exec_args_with<'a>(&'a self, flags: &[&'a str], command: &[&'a str]) -> Vec<&'a str> {
+        let mut args = self.args(&["exec", &self.name]);
+        args.extend_from_slice(flags);
+        args.push("--");
+        args.extend_from_slice(command);
+        args
+    }
+
+    /// Get the shell command line that [`Container::exec`] would run, without
+    /// running it
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - An array of command arguments
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-exec-command-line", "ubuntu:16.04").unwrap();
+    /// assert_eq!(container.exec_command_line(&["echo", "hello"]), "lxc exec test-exec-command-line -- echo hello");
+    /// ```
+    pub fn exec_command_line(&self, command: &[&str]) -> String {
+        command_line(&self.exec_args(command))
+    }
+
+    /// Run a command in an LXD container and capture its output
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - An array of command arguments
+    ///
+    /// # Return
+    ///
+    /// The exit status and the raw bytes written to stdout and stderr, which
+    /// are not required to be UTF-8
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while executing will be returned, including
+    /// the stderr of the command if it fails
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-exec-output", "ubuntu:16.04").unwrap();
+    /// let output = container.exec_output(&["sh", "-c", "echo hello; echo world >&2"]).unwrap();
+    /// assert_eq!(output.stdout, b"hello\n");
+    /// assert_eq!(output.stderr, b"world\n");
+    /// ```
+    pub fn exec_output(&mut self, command: &[&str]) -> Result<process::Output> {
+        lxc_capture(&self.exec_args(command))
+    }
+
+    /// Wait for cloud-init in the container to finish, polling once a second
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait before giving up
+    ///
+    /// # Return
+    ///
+    /// An empty tuple once cloud-init is done, including a degraded done, or
+    /// is disabled
+    ///
+    /// # Errors
+    ///
+    /// Errors will be returned if cloud-init fails, or does not finish before
+    /// the timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    /// use std::time::Duration;
+    ///
+    /// let mut container = Container::new(Location::Local, "test-cloud-init", "ubuntu:16.04").unwrap();
+    /// container.wait_for_cloud_init(Duration::from_secs(300)).unwrap();
+    /// ```
+    pub fn wait_for_cloud_init(&mut self, timeout: Duration) -> Result<()> {
+        let start = Instant::now();
+        loop {
+            // cloud-init exits unsuccessfully when it failed, and when it
+            // finished in a degraded state, so decide on the printed status
+            let args = self.exec_args(&["cloud-init", "status"]);
+            let output = lxc_capture_status(&args)?;
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let status = stdout.lines().find_map(|line| line.strip_prefix("status:")).map(|status| status.trim());
+            match status {
+                Some("done") | Some("disabled") => return Ok(()),
+                Some("error") => return Err(Error::Other(
+                    format!("LXD container: {} cloud-init failed", self.name)
+                )),
+                Some(_) => (),
+                None => if !output.status.success() {
+                    return Err(Error::command_failed(&args, output.status, &output.stderr));
+                }
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(Error::TimedOut(
+                    format!("LXD container: {} cloud-init did not finish in {:?}", self.name, timeout)
+                ));
+            }
+
+            thread::sleep(Duration::from_secs(1));
+        }
+    }
+
+    /// Follow a file in the container, like `tail -f`
+    ///
+    /// Following stops when `on_line` returns false, which terminates the
+    /// `tail` process in the container.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file in the container
+    /// * `on_line` - Called with each line, returning true to keep following
+    ///
+    /// # Return
+    ///
+    /// An empty tuple once following stops
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while following will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-tail-follow", "ubuntu:16.04").unwrap();
+    /// container.tail_follow("/var/log/syslog", |line| {
+    ///     println!("{}", line);
+    ///     !line.contains("Startup finished")
+    /// }).unwrap();
+    /// ```
+    pub fn tail_follow<F: FnMut(&str) -> bool>(&mut self, path: &str, mut on_line: F) -> Result<()> {
+        let mut stopped = false;
+        let status = lxc_lines(&self.exec_args(&["tail", "-F", path]), |line| {
+            let follow = on_line(line);
+            stopped = !follow;
+            follow
+        })?;
+
+        if stopped || status.success() {
+            Ok(())
+        } else {
+            Err(Error::Other(
+                format!("LXD container: {} following {} failed with {}", self.name, path, status)
+            ))
+        }
+    }
+
+    /// Mount a path in an LXD container
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the mount
+    /// * `source` - The source path to mount
+    /// * `dest` - The destination of the mount
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while mounting will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-mount", "ubuntu:16.04").unwrap();
+    /// container.mount("source", ".", "/root/source").unwrap();
+    /// ```
+    pub fn mount<P: AsRef<Path>>(&mut self, name: &str, source: P, dest: &str) -> Result<()> {
+        let source = source.as_ref().display().to_string();
+        self.add_device(name, DeviceKind::Disk, &[("source", &source), ("path", dest)])
+    }
+
+    /// Attach a custom storage volume to an LXD container
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the device
+    /// * `pool` - The storage pool of the volume
+    /// * `volume` - The name of the volume
+    /// * `path` - The path to mount the volume at in the container
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while attaching will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-attach-volume", "ubuntu:16.04").unwrap();
+    /// container.attach_volume("data", "default", "shared-data", "/srv/data").unwrap();
+    /// ```
+    pub fn attach_volume(&mut self, name: &str, pool: &str, volume: &str, path: &str) -> Result<()> {
+        self.add_device(name, DeviceKind::Disk, &[("pool", pool), ("source", volume), ("path", path)])
+    }
+
+    /// Apply a profile to the container, after the profiles it already has
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The name of the profile
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while adding the profile will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-add-profile", "ubuntu:16.04").unwrap();
+    /// container.add_profile("limits").unwrap();
+    /// assert!(container.info().unwrap().profiles.contains(&"limits".to_string()));
+    /// ```
+    pub fn add_profile(&mut self, profile: &str) -> Result<()> {
+        lxc(&self.args(&["profile", "add", &self.name, profile]))
+    }
+
+    /// Remove a profile from the container
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The name of the profile
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while removing the profile will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-remove-profile", "ubuntu:16.04").unwrap();
+    /// container.add_profile("limits").unwrap();
+    /// container.remove_profile("limits").unwrap();
+    /// ```
+    pub fn remove_profile(&mut self, profile: &str) -> Result<()> {
+        lxc(&self.args(&["profile", "remove", &self.name, profile]))
+    }
+
+    /// Add a device to the container
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the device
+    /// * `kind` - The type of the device
+    /// * `options` - The config keys and values of the device
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while adding the device will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, DeviceKind, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-add-device", "ubuntu:16.04").unwrap();
+    /// container.add_device("fuse", DeviceKind::UnixChar, &[("path", "/dev/fuse")]).unwrap();
+    /// ```
+    pub fn add_device(&mut self, name: &str, kind: DeviceKind, options: &[(&str, &str)]) -> Result<()> {
+        let options: Vec<String> = options.iter().map(|(key, value)| {
+            format!("{}={}", key, value)
+        }).collect();
+
+        let mut args = self.args(&["config", "device", "add", &self.name, name, kind.as_str()]);
+        for arg in options.iter() {
+            args.push(arg);
+        }
+        lxc(&args)
+    }
+
+    /// Forward a TCP port on the host to the container
+    ///
+    /// This adds a `proxy` device, which can be removed with
+    /// [`Container::remove_device`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the device
+    /// * `host` - The address to listen on, on the host, `0.0.0.0:8080` for example
+    /// * `container` - The address to connect to, in the container, `127.0.0.1:80` for example
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while adding the device will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-forward-port", "ubuntu:16.04").unwrap();
+    /// container.forward_port("http", "0.0.0.0:8080", "127.0.0.1:80").unwrap();
+    /// ```
+    pub fn forward_port(&mut self, name: &str, host: &str, container: &str) -> Result<()> {
+        let listen = format!("tcp:{}", host);
+        let connect = format!("tcp:{}", container);
+        self.add_device(name, DeviceKind::Proxy, &[("listen", &listen), ("connect", &connect)])
+    }
+
+    /// Remove a device from the container
+    ///
+    /// Devices inherited from a profile cannot be removed this way, they can
+    /// be hidden with a device of type [`DeviceKind::None`] instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the device
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while removing the device will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, DeviceKind, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-remove-device", "ubuntu:16.04").unwrap();
+    /// container.add_device("fuse", DeviceKind::UnixChar, &[("path", "/dev/fuse")]).unwrap();
+    /// container.remove_device("fuse").unwrap();
+    /// ```
+    pub fn remove_device(&mut self, name: &str) -> Result<()> {
+        lxc(&self.args(&["config", "device", "remove", &self.name, name]))
+    }
+
+    /// Get the devices of the container
+    ///
+    /// Only devices added to the container itself are returned, not ones
+    /// inherited from profiles.
+    ///
+    /// # Return
+    ///
+    /// The config of each device, by device name
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-devices", "ubuntu:16.04").unwrap();
+    /// container.mount("source", "/tmp", "/mnt/source").unwrap();
+    /// assert_eq!(container.devices().unwrap()["source"]["path"], "/mnt/source");
+    /// ```
+    pub fn devices(&self) -> Result<BTreeMap<String, BTreeMap<String, String>>> {
+        Ok(self.info()?.devices)
+    }
+
+    /// Override a device inherited from a profile
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the device in the profile
+    /// * `overrides` - Device config keys and values to set on the container
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while overriding will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-override-device", "ubuntu:16.04").unwrap();
+    /// container.override_device("root", &[("size", "20GiB")]).unwrap();
+    /// ```
+    pub fn override_device(&mut self, name: &str, overrides: &[(&str, &str)]) -> Result<()> {
+        let overrides: Vec<String> = overrides.iter().map(|(key, value)| {
+            format!("{}={}", key, value)
+        }).collect();
+
+        let mut args = self.args(&["config", "device", "override", &self.name, name]);
+        for arg in overrides.iter() {
+            args.push(arg);
+        }
+        lxc(&args)
+    }
+
+    /// Set the size of the root disk of the container
+    ///
+    /// If the root disk is inherited from a profile, it is overridden on the
+    /// container.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The new size of the root disk in bytes
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while resizing will be returned. If the
+    /// storage pool of the root disk cannot enforce quotas, an error of kind
+    /// `Unsupported` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-set-root-size", "ubuntu:16.04").unwrap();
+    /// container.set_root_size(20 * 1024 * 1024 * 1024).unwrap();
+    /// ```
+    pub fn set_root_size(&mut self, bytes: u64) -> Result<()> {
+        let info = self.info()?;
+        let pool = info.expanded_devices.get("root").and_then(|root| root.get("pool")).ok_or_else(|| {
+            Error::NotFound(
+                format!("LXD container: {} has no root disk", self.name)
+            )
+        })?;
+
+        let storage = StoragePool::new(self.location.clone(), pool)?;
+        if !storage.supports_quotas() {
+            return Err(Error::Unsupported(
+                format!("LXD container: storage pool {} uses the {} driver, which does not support quotas", storage.name, storage.driver)
+            ));
+        }
+
+        self.set_device_config(&info, "root", &[("size", &format_bytes(bytes))])
+    }
+
+    /// Set config keys on a device, overriding the device if it is inherited
+    /// from a profile
+    fn set_device_config(&mut self, info: &Info, device: &str, config: &[(&str, &str)]) -> Result<()> {
+        if info.devices.contains_key(device) {
+            for (key, value) in config.iter() {
+                lxc(&self.args(&["config", "device", "set", &self.name, device, key, value]))?;
+            }
+            Ok(())
+        } else {
+            self.override_device(device, config)
+        }
+    }
+
+    /// Limit the number of CPUs the container can use
     ///
     /// # Arguments
     ///
-    /// * `location` - The location of the host
-    /// * `name` - The name of the container
-    /// * `base` - The base distribution to use, `ubuntu:16.04` for example
+    /// * `count` - The number of CPUs
     ///
     /// # Return
     ///
-    /// The newly created LXD container
+    /// An empty tuple on success
     ///
     /// # Errors
     ///
-    /// Errors that are encountered while creating container will be returned
+    /// Errors that are encountered while setting the limit will be returned,
+    /// or an error if `count` is zero
     ///
     /// # Example
     ///
     /// ```
     /// use lxd::{Container, Location};
     ///
-    /// let mut container = Container::new(Location::Local, "test-new", "ubuntu:16.04").unwrap();
+    /// let mut container = Container::new(Location::Local, "test-limit-cpu", "ubuntu:16.04").unwrap();
+    /// container.limit_cpu(2).unwrap();
     /// ```
-    pub fn new(location: Location, name: &str, base: &str) -> io::Result<Self> {
-        let full_name = match location {
-            Location::Local => name.to_string(),
-            Location::Remote(remote) => format!("{}:{}", remote, name)
-        };
-
-        lxc(&["launch", base, &full_name, "-e", "-n", "lxdbr0"])?;
-
-        // XXX: https://bugzilla.redhat.com/show_bug.cgi?id=1419315
-        lxc(&["exec", &full_name, "--mode=non-interactive", "-n", "--", "touch", "/etc/fstab"])?;
-
-        // Hack to wait for network up and running
-        lxc(&["exec", &full_name, "--mode=non-interactive", "-n", "--", "dhclient"])?;
+    pub fn limit_cpu(&mut self, count: u32) -> Result<()> {
+        if count == 0 {
+            return Err(Error::InvalidInput(
+                format!("LXD container: {} cannot be limited to 0 CPUs", self.name)
+            ));
+        }
 
-        Ok(Container {
-            name: full_name
-        })
+        self.set_config("limits.cpu", &count.to_string())
     }
 
-    /// Create a new privileged LXD container
+    /// Limit the memory the container can use
     ///
     /// # Arguments
     ///
-    /// * `location` - The location of the host
-    /// * `name` - The name of the container
-    /// * `base` - The base distribution to use, `ubuntu:16.04` for example
+    /// * `limit` - A number of bytes, or a [`MemoryLimit`]
     ///
     /// # Return
     ///
-    /// The newly created LXD container
+    /// An empty tuple on success
     ///
     /// # Errors
     ///
-    /// Errors that are encountered while creating container will be returned
+    /// Errors that are encountered while setting the limit will be returned,
+    /// or an error if a percentage is over 100
     ///
     /// # Example
     ///
     /// ```
-    /// use lxd::{Container, Location};
+    /// use lxd::{Container, Location, MemoryLimit};
     ///
-    /// let mut container = unsafe { Container::new_privileged(Location::Local, "test-new-privileged", "ubuntu:16.04").unwrap() };
+    /// let mut container = Container::new(Location::Local, "test-limit-memory", "ubuntu:16.04").unwrap();
+    /// container.limit_memory(2 * 1024 * 1024 * 1024).unwrap();
+    /// container.limit_memory(MemoryLimit::Percent(25)).unwrap();
     /// ```
-    pub unsafe fn new_privileged(location: Location, name: &str, base: &str) -> io::Result<Self> {
-        let full_name = match location {
-            Location::Local => name.to_string(),
-            Location::Remote(remote) => format!("{}:{}", remote, name)
-        };
-
-        lxc(&["launch", base, &full_name, "-e", "-n", "lxdbr0",
-            "-c", "security.privileged=true",
-            "-c", "raw.lxc=lxc.apparmor.profile=unconfined"])?;
-
-        // XXX: https://bugzilla.redhat.com/show_bug.cgi?id=1419315
-        lxc(&["exec", &full_name, "--mode=non-interactive", "-n", "--", "touch", "/etc/fstab"])?;
-
-        // Hack to wait for network up and running
-        lxc(&["exec", &full_name, "--mode=non-interactive", "-n", "--", "dhclient"])?;
-
-        Ok(Container {
-            name: full_name
-        })
-    }
+    pub fn limit_memory<L: Into<MemoryLimit>>(&mut self, limit: L) -> Result<()> {
+        let limit = limit.into();
+        if let MemoryLimit::Percent(percent) = limit {
+            if percent > 100 {
+                return Err(Error::InvalidInput(
+                    format!("LXD container: {}% is not a valid memory limit", percent)
+                ));
+            }
+        }
 
-    /// Get full name of container
-    pub fn name(&self) -> &str {
-        &self.name
+        self.set_config("limits.memory", &limit.value())
     }
 
-    /// Create a snapshot of a container
+    /// Choose whether the container can swap out memory
     ///
     /// # Arguments
     ///
-    /// * `name` - name of the new snapshot
+    /// * `on` - True to allow swapping
     ///
     /// # Return
     ///
-    /// A new snapshot on success
+    /// An empty tuple on success
     ///
     /// # Errors
     ///
-    /// Errors that are encountered while creating snapshot will be returned
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use lxd::{Container, Location, Snapshot};
-    ///
-    /// let container = Container::new(Location::Local, "test-snapshot", "ubuntu:16.04").unwrap();
-    /// container.snapshot("test-snapshot").unwrap();
-    /// ```
-    pub fn snapshot<'a>(&'a self, name: &str) -> io::Result<Snapshot<'a>> {
-        Snapshot::new(self, name)
+    /// Errors that are encountered while setting the limit will be returned
+    pub fn limit_memory_swap(&mut self, on: bool) -> Result<()> {
+        self.set_config("limits.memory.swap", &on.to_string())
     }
 
-    /// Run a command in an LXD container
+    /// Set IO limits on the container and one of its disk devices
+    ///
+    /// If the device is inherited from a profile, it is overridden on the
+    /// container.
     ///
     /// # Arguments
     ///
-    /// * `command` - An array of command arguments
+    /// * `device` - The name of the disk device
+    /// * `limits` - The limits to set, limits that are `None` are left unchanged
     ///
     /// # Return
     ///
@@ -138,31 +2310,57 @@ impl Container {
     ///
     /// # Errors
     ///
-    /// Errors that are encountered while executing will be returned
+    /// Errors that are encountered while setting limits will be returned
     ///
     /// # Example
     ///
     /// ```
-    /// use lxd::{Container, Location};
-    ///
-    /// let mut container = Container::new(Location::Local, "test-exec", "ubuntu:16.04").unwrap();
-    /// container.exec(&["echo", "hello"]).unwrap();
+    /// use lxd::{Container, IoLimit, IoLimits, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-io-limits", "ubuntu:16.04").unwrap();
+    /// container.set_io_limits("root", &IoLimits {
+    ///     priority: Some(2),
+    ///     read: Some(IoLimit::Bandwidth(30 * 1024 * 1024)),
+    ///     write: Some(IoLimit::Iops(200)),
+    /// }).unwrap();
     /// ```
-    pub fn exec(&mut self, command: &[&str]) -> io::Result<()> {
-        let mut args = vec!["exec", &self.name, "--"];
-        for arg in command.as_ref().iter() {
-            args.push(arg.as_ref());
+    pub fn set_io_limits(&mut self, device: &str, limits: &IoLimits) -> Result<()> {
+        if let Some(priority) = limits.priority {
+            if priority > 10 {
+                return Err(Error::InvalidInput(
+                    format!("LXD container: disk priority {} is not between 0 and 10", priority)
+                ));
+            }
+            self.set_config("limits.disk.priority", &priority.to_string())?;
         }
-        lxc(&args)
+
+        let read = limits.read.map(|read| read.value());
+        let write = limits.write.map(|write| write.value());
+        let mut config = Vec::new();
+        if let Some(ref read) = read {
+            config.push(("limits.read", read.as_str()));
+        }
+        if let Some(ref write) = write {
+            config.push(("limits.write", write.as_str()));
+        }
+
+        if !config.is_empty() {
+            let info = self.info()?;
+            self.set_device_config(&info, device, &config)?;
+        }
+
+        Ok(())
     }
 
-    /// Mount a path in an LXD container
+    /// Set the MAC address of a network device
+    ///
+    /// If the device is inherited from a profile, it is overridden on the
+    /// container.
     ///
     /// # Arguments
     ///
-    /// * `name` - The name of the mount
-    /// * `source` - The source path to mount
-    /// * `dest` - The destination of the mount
+    /// * `device` - The name of the nic device
+    /// * `mac` - The MAC address, such as `00:16:3e:12:34:56`
     ///
     /// # Return
     ///
@@ -170,18 +2368,38 @@ impl Container {
     ///
     /// # Errors
     ///
-    /// Errors that are encountered while mounting will be returned
+    /// Errors that are encountered while setting the address will be returned.
+    /// An error of kind `InvalidInput` is returned if the address is malformed
+    /// or the device is not a nic.
     ///
     /// # Example
     ///
     /// ```
     /// use lxd::{Container, Location};
     ///
-    /// let mut container = Container::new(Location::Local, "test-mount", "ubuntu:16.04").unwrap();
-    /// container.mount("source", ".", "/root/source").unwrap();
+    /// let mut container = Container::new(Location::Local, "test-set-mac", "ubuntu:16.04").unwrap();
+    /// container.set_mac("eth0", "00:16:3e:12:34:56").unwrap();
     /// ```
-    pub fn mount<P: AsRef<Path>>(&mut self, name: &str, source: P, dest: &str) -> io::Result<()> {
-        lxc(&["config", "device", "add", &self.name, name, "disk", &format!("source={}", source.as_ref().display()), &format!("path={}", dest)])
+    pub fn set_mac(&mut self, device: &str, mac: &str) -> Result<()> {
+        let octets: Vec<&str> = mac.split(':').collect();
+        if octets.len() != 6 || !octets.iter().all(|octet| octet.len() == 2 && octet.chars().all(|c| c.is_ascii_hexdigit())) {
+            return Err(Error::InvalidInput(
+                format!("LXD container: {:?} is not a valid MAC address", mac)
+            ));
+        }
+
+        let info = self.info()?;
+        match info.expanded_devices.get(device).and_then(|config| config.get("type")) {
+            Some(kind) if kind == "nic" => (),
+            Some(kind) => return Err(Error::InvalidInput(
+                format!("LXD container: device {} is a {}, not a nic", device, kind)
+            )),
+            None => return Err(Error::NotFound(
+                format!("LXD container: device {} not found", device)
+            ))
+        }
+
+        self.set_device_config(&info, device, &[("hwaddr", mac)])
     }
 
     /// Push a file to the LXD container
@@ -198,7 +2416,9 @@ impl Container {
     ///
     /// # Errors
     ///
-    /// Errors that are encountered while pushing will be returned
+    /// Errors that are encountered while pushing will be returned. If `lxc` is
+    /// confined by snap and cannot read the source, an error of kind
+    /// `PermissionDenied` is returned.
     ///
     /// # Example
     ///
@@ -215,24 +2435,89 @@ impl Container {
     ///     container.push(tmp.path(), "/root", true).unwrap();
     /// }
     /// ```
-    pub fn push<P: AsRef<Path>>(&mut self, source: P, dest: &str, recursive: bool) -> io::Result<()> {
+    pub fn push<P: AsRef<Path>>(&mut self, source: P, dest: &str, recursive: bool) -> Result<()> {
+        self.push_with(source, dest, recursive, &PushOptions::default())
+    }
+
+    /// Push a file to the LXD container, setting its ownership and mode
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source of the file in the host
+    /// * `dest` - The destination of the file in the container
+    /// * `recursive` - The source is a directory
+    /// * `options` - The ownership and mode of the file in the container
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while pushing will be returned. If `lxc` is
+    /// confined by snap and cannot read the source, an error of kind
+    /// `PermissionDenied` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, PushOptions};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-push-with", "ubuntu:16.04").unwrap();
+    /// container.push_with("deploy.sh", "/usr/local/bin/deploy.sh", false, &PushOptions {
+    ///     uid: Some(1000),
+    ///     gid: Some(1000),
+    ///     mode: Some(0o755),
+    /// }).unwrap();
+    /// ```
+    pub fn push_with<P: AsRef<Path>>(&mut self, source: P, dest: &str, recursive: bool, options: &PushOptions) -> Result<()> {
+        check_readable(source.as_ref())?;
+
+        let source = format!("{}", source.as_ref().display());
+        let dest = format!("{}/{}", self.name, dest);
+        let options = options.args();
+
+        let mut args = self.args(&["file", "push", "--quiet"]);
         if recursive {
-            lxc(&[
-                "file",
-                "push",
-                "--quiet",
-                "--recursive",
-                &format!("{}", source.as_ref().display()),
-                &format!("{}/{}", self.name, dest)
-            ])
+            args.push("--recursive");
+        }
+        for arg in options.iter() {
+            args.push(arg);
+        }
+        args.extend_from_slice(&[&source, &dest]);
+        lxc(&args)
+    }
+
+    /// Create a directory in the LXD container
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the directory in the container
+    /// * `parents` - Create missing parent directories, and succeed if the
+    ///   directory already exists, like `mkdir -p`
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating the directory will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-mkdir", "ubuntu:16.04").unwrap();
+    /// container.mkdir("/opt/app/config", true).unwrap();
+    /// container.write_file("/opt/app/config/app.toml", b"debug = false\n").unwrap();
+    /// ```
+    pub fn mkdir(&mut self, path: &str, parents: bool) -> Result<()> {
+        if parents {
+            lxc(&self.exec_args(&["mkdir", "-p", "--", path]))
         } else {
-            lxc(&[
-                "file",
-                "push",
-                "--quiet",
-                &format!("{}", source.as_ref().display()),
-                &format!("{}/{}", self.name, dest)
-            ])
+            lxc(&self.exec_args(&["mkdir", "--", path]))
         }
     }
 
@@ -268,30 +2553,279 @@ impl Container {
     ///     container.pull("/root/artifacts", tmp.path(), true).unwrap();
     /// }
     /// ```
-    pub fn pull<P: AsRef<Path>>(&mut self, source: &str, dest: P, recursive: bool) -> io::Result<()> {
+    pub fn pull<P: AsRef<Path>>(&mut self, source: &str, dest: P, recursive: bool) -> Result<()> {
         if recursive {
-            lxc(&[
+            lxc(&self.args(&[
                 "file",
                 "pull",
                 "--quiet",
                 "--recursive",
                 &format!("{}/{}", self.name, source),
                 &format!("{}", dest.as_ref().display())
-            ])
+            ]))
         } else {
-            lxc(&[
+            lxc(&self.args(&[
                 "file",
                 "pull",
                 "--quiet",
                 &format!("{}/{}", self.name, source),
                 &format!("{}", dest.as_ref().display())
-            ])
+            ]))
+        }
+    }
+
+    /// Pull a file from the LXD container, preserving its ownership and mode
+    ///
+    /// The numeric uid and gid inside the container are applied to the host
+    /// files as they are, which usually requires running as root.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source of the file in the container
+    /// * `dest` - The destination of the file in the host
+    /// * `recursive` - The source is a directory
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while pulling or applying metadata will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate lxd;
+    /// extern crate tempdir;
+    ///
+    /// use lxd::{Container, Location};
+    /// use tempdir::TempDir;
+    ///
+    /// fn main() {
+    ///     let mut container = Container::new(Location::Local, "test-pull-preserve", "ubuntu:16.04").unwrap();
+    ///     let tmp = TempDir::new("").unwrap();
+    ///     container.pull_preserve("/etc", tmp.path(), true).unwrap();
+    /// }
+    /// ```
+    pub fn pull_preserve<P: AsRef<Path>>(&mut self, source: &str, dest: P, recursive: bool) -> Result<()> {
+        let dest = dest.as_ref();
+        let target = match Path::new(source).file_name() {
+            Some(file_name) if dest.is_dir() => dest.join(file_name),
+            _ => dest.to_path_buf()
+        };
+
+        self.pull(source, dest, recursive)?;
+
+        let mut command = vec!["find", source];
+        if !recursive {
+            command.extend_from_slice(&["-maxdepth", "0"]);
+        }
+        command.extend_from_slice(&["-printf", "%y %U %G %m %P\\0"]);
+        let output = self.exec_output(&command)?.stdout;
+
+        for entry in output.split(|&b| b == 0).filter(|entry| !entry.is_empty()) {
+            let entry = String::from_utf8_lossy(entry);
+            let mut parts = entry.splitn(5, ' ');
+            let (kind, uid, gid, mode, relative) = match (parts.next(), parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(kind), Some(uid), Some(gid), Some(mode), Some(relative)) => (kind, uid, gid, mode, relative),
+                _ => return Err(Error::Other(
+                    format!("LXD container: failed to parse metadata of {}: {:?}", source, entry)
+                ))
+            };
+            let parse_error = |err| Error::Other(
+                format!("LXD container: failed to parse metadata of {}: {}", source, err)
+            );
+            let uid = uid.parse::<u32>().map_err(parse_error)?;
+            let gid = gid.parse::<u32>().map_err(parse_error)?;
+            let mode = u32::from_str_radix(mode, 8).map_err(parse_error)?;
+
+            let path = if relative.is_empty() {
+                target.clone()
+            } else {
+                target.join(relative)
+            };
+
+            if kind == "l" {
+                unix_fs::lchown(&path, Some(uid), Some(gid))?;
+            } else {
+                unix_fs::chown(&path, Some(uid), Some(gid))?;
+                fs::set_permissions(&path, fs::Permissions::from_mode(mode))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pull a file from the LXD container into memory
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source of the file in the container
+    ///
+    /// # Return
+    ///
+    /// The raw contents of the file
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while pulling will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-pull-bytes", "ubuntu:16.04").unwrap();
+    /// let hostname = container.pull_bytes("/etc/hostname").unwrap();
+    /// ```
+    pub fn pull_bytes(&mut self, source: &str) -> Result<Vec<u8>> {
+        self.read_file(source)
+    }
+
+    /// Read a file in the LXD container into memory, see
+    /// [`Container::pull_bytes`]
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file in the container
+    ///
+    /// # Return
+    ///
+    /// The raw contents of the file
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while pulling will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-read-file", "ubuntu:16.04").unwrap();
+    /// let hostname = container.read_file("/etc/hostname").unwrap();
+    /// assert_eq!(hostname, b"test-read-file\n");
+    /// ```
+    pub fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        lxc_output(&self.args(&[
+            "file",
+            "pull",
+            "--quiet",
+            &format!("{}/{}", self.name, path),
+            "-"
+        ]))
+    }
+
+    /// Write a file in the LXD container from memory, replacing it if it
+    /// exists
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file in the container
+    /// * `contents` - The raw contents of the file
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while pushing will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let mut container = Container::new(Location::Local, "test-write-file", "ubuntu:16.04").unwrap();
+    /// container.write_file("/etc/motd", b"Welcome\n").unwrap();
+    /// assert_eq!(container.read_file("/etc/motd").unwrap(), b"Welcome\n");
+    /// ```
+    pub fn write_file(&mut self, path: &str, contents: &[u8]) -> Result<()> {
+        lxc_input(&self.args(&[
+            "file",
+            "push",
+            "--quiet",
+            "-",
+            &format!("{}/{}", self.name, path)
+        ]), contents)
+    }
+
+    /// Check if a file exists in the LXD container
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file in the container
+    ///
+    /// # Return
+    ///
+    /// True if the file exists
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while checking will be returned, including
+    /// when the container does not exist or is not running
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    ///
+    /// let container = Container::new(Location::Local, "test-file-exists", "ubuntu:16.04").unwrap();
+    /// assert!(container.file_exists("/etc/hostname").unwrap());
+    /// assert!(!container.file_exists("/etc/missing").unwrap());
+    /// ```
+    pub fn file_exists(&self, path: &str) -> Result<bool> {
+        // lxc exits with 1 for its own errors too, so print the result in the
+        // container rather than relying on the exit status of test
+        let stdout = lxc_output(&self.exec_args(&[
+            "sh", "-c", "if test -e \"$1\"; then echo yes; else echo no; fi", "sh", path
+        ]))?;
+        match String::from_utf8_lossy(&stdout).trim() {
+            "yes" => Ok(true),
+            "no" => Ok(false),
+            output => Err(Error::Other(
+                format!("LXD container: {} checking {} printed {:?}", self.name, path, output)
+            ))
         }
     }
 }
 
+impl PartialEq for Container {
+    /// Containers are equal if they refer to the same container on the same host
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location};
+    /// use std::collections::HashSet;
+    ///
+    /// let container = Container::new(Location::Local, "test-eq", "ubuntu:16.04").unwrap();
+    /// let mut containers = HashSet::new();
+    /// containers.insert(Container::attach(Location::Local, "test-eq").unwrap());
+    /// containers.insert(Container::attach(Location::Local, "test-eq").unwrap());
+    /// assert_eq!(containers.len(), 1);
+    /// assert!(containers.contains(&container));
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        self.location == other.location && self.name == other.name
+    }
+}
+
+impl Eq for Container {}
+
+impl Hash for Container {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.location.hash(state);
+        self.name.hash(state);
+    }
+}
+
 impl Drop for Container {
     fn drop(&mut self) {
-        let _ = lxc(&["stop", &self.name]);
+        if self.owned && self.ephemeral {
+            let _ = lxc(&self.args(&["stop", &self.name]));
+        }
     }
 }
@@ -0,0 +1,48 @@
+/// The type of an LXD device
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DeviceKind {
+    /// A filesystem mount or storage volume
+    Disk,
+    /// A network interface
+    Nic,
+    /// A GPU from the host
+    Gpu,
+    /// A port forwarded between the host and the container
+    Proxy,
+    /// A character device from the host
+    UnixChar,
+    /// A block device from the host
+    UnixBlock,
+    /// A USB device from the host
+    Usb,
+    /// An InfiniBand device from the host
+    Infiniband,
+    /// A device that hides one inherited from a profile
+    None,
+}
+
+impl DeviceKind {
+    /// Get the LXD name of the device type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::DeviceKind;
+    ///
+    /// assert_eq!(DeviceKind::Disk.as_str(), "disk");
+    /// assert_eq!(DeviceKind::UnixChar.as_str(), "unix-char");
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            DeviceKind::Disk => "disk",
+            DeviceKind::Nic => "nic",
+            DeviceKind::Gpu => "gpu",
+            DeviceKind::Proxy => "proxy",
+            DeviceKind::UnixChar => "unix-char",
+            DeviceKind::UnixBlock => "unix-block",
+            DeviceKind::Usb => "usb",
+            DeviceKind::Infiniband => "infiniband",
+            DeviceKind::None => "none",
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::process;
+use std::result;
+
+use super::command_line;
+
+/// An error encountered while controlling LXD
+#[derive(Debug)]
+pub enum Error {
+    /// The `lxc` command could not be found
+    LxcNotFound,
+    /// An `lxc` command exited unsuccessfully
+    CommandFailed {
+        /// The arguments passed to `lxc`
+        args: Vec<String>,
+        /// The exit status of `lxc`
+        status: process::ExitStatus,
+        /// The error output of `lxc`, if it was captured
+        stderr: String,
+    },
+    /// The JSON output of an `lxc` command could not be parsed
+    Json(serde_json::Error),
+    /// A container, image or other object does not exist
+    NotFound(String),
+    /// An argument is not valid
+    InvalidInput(String),
+    /// The host does not support the requested operation
+    Unsupported(String),
+    /// Waiting for LXD took longer than allowed
+    TimedOut(String),
+    /// Any other failure, described by the message, such as LXD not being
+    /// in a state where the operation can be done, a command in the container
+    /// failing, or output from `lxc` or the LXD socket that cannot be parsed
+    Other(String),
+    /// An I/O error, such as reading a file to push into a container
+    Io(io::Error),
+}
+
+/// A result with an LXD error
+pub type Result<T> = result::Result<T, Error>;
+
+impl Error {
+    /// Create a `CommandFailed` error for an `lxc` command
+    pub(crate) fn command_failed(args: &[&str], status: process::ExitStatus, stderr: &[u8]) -> Self {
+        Error::CommandFailed {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            status,
+            stderr: String::from_utf8_lossy(stderr).trim().to_string(),
+        }
+    }
+
+    /// Convert an error from spawning `lxc`, where `NotFound` means that
+    /// `lxc` is not installed
+    pub(crate) fn spawn(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::NotFound {
+            Error::LxcNotFound
+        } else {
+            Error::Io(err)
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::LxcNotFound => write!(f, "LXD: lxc command not found"),
+            Error::CommandFailed { ref args, ref status, ref stderr } => {
+                let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+                write!(f, "LXD {} failed with {}", command_line(&args), status)?;
+                if !stderr.is_empty() {
+                    write!(f, ": {}", stderr)?;
+                }
+                Ok(())
+            },
+            Error::Json(ref err) => write!(f, "LXD: failed to parse json: {}", err),
+            Error::NotFound(ref message)
+            | Error::InvalidInput(ref message)
+            | Error::Unsupported(ref message)
+            | Error::TimedOut(ref message)
+            | Error::Other(ref message) => write!(f, "{}", message),
+            Error::Io(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Json(ref err) => Some(err),
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+impl From<Error> for io::Error {
+    /// Convert to an I/O error, for callers that still use `io::Result`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Error;
+    /// use std::io;
+    ///
+    /// let err = io::Error::from(Error::NotFound("LXD info: test not found".to_string()));
+    /// assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    /// assert_eq!(err.to_string(), "LXD info: test not found");
+    /// ```
+    fn from(err: Error) -> Self {
+        let kind = match err {
+            Error::Io(err) => return err,
+            Error::LxcNotFound | Error::NotFound(_) => io::ErrorKind::NotFound,
+            Error::InvalidInput(_) => io::ErrorKind::InvalidInput,
+            Error::Unsupported(_) => io::ErrorKind::Unsupported,
+            Error::TimedOut(_) => io::ErrorKind::TimedOut,
+            Error::CommandFailed { .. } | Error::Json(_) | Error::Other(_) => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, err)
+    }
+}
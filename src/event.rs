@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Lines, Read};
+use std::process::{Child, ChildStdout, Stdio};
+use std::thread::{self, JoinHandle};
+
+use super::{lxc_command, Error, Location, Operation, Result};
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+/// LXD event
+pub struct Event {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub timestamp: String,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+    #[serde(default)]
+    pub location: String,
+    #[serde(default)]
+    pub project: String,
+}
+
+/// The kind of an LXD event, with its metadata
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EventKind {
+    /// A lifecycle event, such as `instance-started`
+    Lifecycle {
+        action: String,
+        source: String,
+    },
+    /// A log message from the daemon
+    Logging {
+        level: String,
+        message: String,
+    },
+    /// An update to a background operation
+    Operation(Box<Operation>),
+    /// An event type not known to this library
+    Other(String),
+}
+
+impl Event {
+    /// Get the kind of the event, parsing its metadata
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Event, EventKind};
+    ///
+    /// let event: Event = serde_json::from_str(r#"{
+    ///     "type": "lifecycle",
+    ///     "timestamp": "2020-01-01T00:00:00Z",
+    ///     "metadata": {"action": "instance-started", "source": "/1.0/instances/test"}
+    /// }"#).unwrap();
+    /// assert_eq!(event.kind(), EventKind::Lifecycle {
+    ///     action: "instance-started".to_string(),
+    ///     source: "/1.0/instances/test".to_string(),
+    /// });
+    /// ```
+    pub fn kind(&self) -> EventKind {
+        let field = |name: &str| {
+            self.metadata.get(name).and_then(|value| value.as_str()).unwrap_or("").to_string()
+        };
+
+        match self.event_type.as_str() {
+            "lifecycle" => EventKind::Lifecycle {
+                action: field("action"),
+                source: field("source"),
+            },
+            "logging" => EventKind::Logging {
+                level: field("level"),
+                message: field("message"),
+            },
+            "operation" => match serde_json::from_value(self.metadata.clone()) {
+                Ok(operation) => EventKind::Operation(Box::new(operation)),
+                Err(_) => EventKind::Other(self.event_type.clone()),
+            },
+            _ => EventKind::Other(self.event_type.clone()),
+        }
+    }
+}
+
+/// A stream of LXD events, from `lxc monitor`
+///
+/// The monitor process is killed when the stream is dropped. If the monitor
+/// fails, such as when the host cannot be reached, the stream ends with the
+/// error.
+pub struct Events {
+    args: Vec<String>,
+    child: Child,
+    lines: Lines<BufReader<ChildStdout>>,
+    stderr: Option<JoinHandle<Vec<u8>>>,
+}
+
+impl Events {
+    /// Wait for the monitor after its output has ended, and check whether
+    /// it failed
+    fn finish(&mut self) -> Result<()> {
+        let stderr = match self.stderr.take() {
+            Some(stderr) => stderr.join().unwrap_or_default(),
+            None => return Ok(())
+        };
+
+        let status = self.child.wait()?;
+        if status.success() {
+            Ok(())
+        } else {
+            let args: Vec<&str> = self.args.iter().map(|arg| arg.as_str()).collect();
+            Err(Error::command_failed(&args, status, &stderr))
+        }
+    }
+}
+
+impl Iterator for Events {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(err)) => return Some(Err(err.into())),
+                None => return self.finish().err().map(Err)
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            return Some(serde_json::from_str::<Event>(&line).map_err(Error::Json));
+        }
+    }
+}
+
+impl Drop for Events {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Stream events from an LXD host
+///
+/// # Arguments
+///
+/// * `location` - The location of the host
+///
+/// # Return
+///
+/// An iterator over the events, which blocks until each event arrives
+///
+/// # Errors
+///
+/// Errors that are encountered while starting the monitor will be returned,
+/// and the iterator returns an error if the monitor fails
+///
+/// # Example
+///
+/// ```
+/// use lxd::{events, EventKind, Location};
+///
+/// for event in events(Location::Local).unwrap() {
+///     if let EventKind::Lifecycle { action, source } = event.unwrap().kind() {
+///         println!("{}: {}", source, action);
+///         break;
+///     }
+/// }
+/// ```
+pub fn events(location: Location) -> Result<Events> {
+    let remote = location.remote().map(|remote| format!("{}:", remote));
+    let mut args = location.args(&["monitor"]);
+    if let Some(ref remote) = remote {
+        args.push(remote);
+    }
+    args.extend_from_slice(&["--format", "json"]);
+
+    let mut cmd = lxc_command(&args);
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(Error::spawn)?;
+    let (stdout, mut stderr) = match (child.stdout.take(), child.stderr.take()) {
+        (Some(stdout), Some(stderr)) => (stdout, stderr),
+        _ => return Err(Error::Other("LXD event: failed to capture monitor output".to_string()))
+    };
+
+    // Read stderr as it is written, so the monitor cannot block on it
+    let stderr = thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stderr.read_to_end(&mut output);
+        output
+    });
+
+    Ok(Events {
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        child,
+        lines: BufReader::new(stdout).lines(),
+        stderr: Some(stderr),
+    })
+}
@@ -0,0 +1,88 @@
+use std::os::unix::process::ExitStatusExt;
+use std::process;
+
+/// How a command run in a container finished
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ExitStatus {
+    /// The command exited with a code
+    ///
+    /// A command killed by a signal in the container is also reported here,
+    /// as 128 plus the signal number, because `lxc exec` exits with that code
+    /// the same way a shell does. See [`ExitStatus::signal`].
+    Exited(i32),
+    /// `lxc` itself was killed by a signal
+    Terminated(i32),
+}
+
+impl ExitStatus {
+    /// Check if the command exited successfully
+    pub fn success(&self) -> bool {
+        *self == ExitStatus::Exited(0)
+    }
+
+    /// Get the exit code of the command, if it was not killed by a signal
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::ExitStatus;
+    ///
+    /// assert_eq!(ExitStatus::Exited(2).code(), Some(2));
+    /// assert_eq!(ExitStatus::Terminated(9).code(), None);
+    /// ```
+    pub fn code(&self) -> Option<i32> {
+        match *self {
+            ExitStatus::Exited(code) => Some(code),
+            ExitStatus::Terminated(_) => None,
+        }
+    }
+
+    /// Get the signal that killed the command, if any
+    ///
+    /// An exit code from 129 to 192 is taken to be 128 plus the number of the
+    /// signal that killed the command in the container. This is a guess, as a
+    /// command that exits normally with such a code cannot be told apart.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::ExitStatus;
+    ///
+    /// assert_eq!(ExitStatus::Exited(137).signal(), Some(9));
+    /// assert_eq!(ExitStatus::Exited(1).signal(), None);
+    /// assert_eq!(ExitStatus::Terminated(15).signal(), Some(15));
+    /// ```
+    pub fn signal(&self) -> Option<i32> {
+        match *self {
+            ExitStatus::Exited(code @ 129..=192) => Some(code - 128),
+            ExitStatus::Exited(_) => None,
+            ExitStatus::Terminated(signal) => Some(signal),
+        }
+    }
+}
+
+impl From<process::ExitStatus> for ExitStatus {
+    /// Convert the exit status of `lxc exec`
+    ///
+    /// Only a signal that killed `lxc` itself is reported as `Terminated`,
+    /// every exit code is kept as it is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::ExitStatus;
+    /// use std::os::unix::process::ExitStatusExt;
+    /// use std::process;
+    ///
+    /// assert_eq!(ExitStatus::from(process::ExitStatus::from_raw(0)), ExitStatus::Exited(0));
+    /// assert_eq!(ExitStatus::from(process::ExitStatus::from_raw(1 << 8)), ExitStatus::Exited(1));
+    /// assert_eq!(ExitStatus::from(process::ExitStatus::from_raw(137 << 8)), ExitStatus::Exited(137));
+    /// assert_eq!(ExitStatus::from(process::ExitStatus::from_raw(15)), ExitStatus::Terminated(15));
+    /// ```
+    fn from(status: process::ExitStatus) -> Self {
+        match status.signal() {
+            Some(signal) => ExitStatus::Terminated(signal),
+            None => ExitStatus::Exited(status.code().unwrap_or_default())
+        }
+    }
+}
@@ -0,0 +1,42 @@
+/// Ownership and permissions to give files pushed into a container
+///
+/// Fields that are `None` are left to the default of `lxc file push`, which
+/// is `root:root` with the mode of the source file.
+///
+/// # Example
+///
+/// ```
+/// use lxd::PushOptions;
+///
+/// let options = PushOptions {
+///     uid: Some(1000),
+///     gid: Some(1000),
+///     mode: Some(0o755),
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PushOptions {
+    /// The owner of the files in the container
+    pub uid: Option<u32>,
+    /// The group of the files in the container
+    pub gid: Option<u32>,
+    /// The permission bits of the files, `0o644` for example
+    pub mode: Option<u32>,
+}
+
+impl PushOptions {
+    /// Get the flags for `lxc file push`
+    pub(crate) fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(uid) = self.uid {
+            args.push(format!("--uid={}", uid));
+        }
+        if let Some(gid) = self.gid {
+            args.push(format!("--gid={}", gid));
+        }
+        if let Some(mode) = self.mode {
+            args.push(format!("--mode={:04o}", mode));
+        }
+        args
+    }
+}
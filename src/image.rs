@@ -1,8 +1,32 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::io;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use super::{lxc_output, Location};
+#[cfg(feature = "socket")]
+use super::socket;
+use super::{lxc, lxc_output, lxc_progress, Error, Inventory, Location, Result};
+use super::snap::check_readable;
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+/// LXD image alias
+///
+/// # Example
+///
+/// ```
+/// use lxd::Alias;
+///
+/// let alias: Alias = serde_json::from_str(r#"{"name": "myapp/latest"}"#).unwrap();
+/// assert_eq!(alias, Alias { name: "myapp/latest".to_string(), description: String::new() });
+///
+/// let json = serde_json::to_string(&alias).unwrap();
+/// assert_eq!(serde_json::from_str::<Alias>(&json).unwrap(), alias);
+/// ```
+pub struct Alias {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
 
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 /// LXD image information
@@ -10,7 +34,7 @@ pub struct Image {
     pub auto_update: bool,
     pub properties: BTreeMap<String, String>,
     pub public: bool,
-    pub aliases: Vec<BTreeMap<String, String>>,
+    pub aliases: Vec<Alias>,
     pub architecture: String,
     pub cached: bool,
     pub filename: String,
@@ -27,6 +51,9 @@ pub struct Image {
 impl Image {
     /// Retrieve LXD container image information from all images
     ///
+    /// With the `socket` feature, the local host is queried through the LXD
+    /// unix socket instead of running `lxc`.
+    ///
     /// # Arguments
     ///
     /// * `location` - The location of the host
@@ -46,18 +73,20 @@ impl Image {
     ///
     /// let images = Image::all(Location::Local).unwrap();
     /// ```
-    pub fn all(location: Location) -> io::Result<Vec<Self>> {
-        let json = match location {
-            Location::Local => lxc_output(&["image", "list", "--format", "json"])?,
-            Location::Remote(remote) => lxc_output(&["image", "list", &format!("{}:", remote), "--format", "json"])?
+    pub fn all(location: Location) -> Result<Vec<Self>> {
+        #[cfg(feature = "socket")]
+        {
+            if location.remote().is_none() {
+                return socket::get("/1.0/images?recursion=1", location.project());
+            }
+        }
+
+        let json = match location.remote() {
+            None => lxc_output(&location.args(&["image", "list", "--format", "json"]))?,
+            Some(remote) => lxc_output(&location.args(&["image", "list", &format!("{}:", remote), "--format", "json"]))?
         };
 
-        serde_json::from_slice::<Vec<Self>>(&json).map_err(|err| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("LXD image: failed to parse json: {}", err)
-            )
-        })
+        serde_json::from_slice::<Vec<Self>>(&json).map_err(Error::Json)
     }
 
     /// Retrieve LXD image information from one image
@@ -75,27 +104,417 @@ impl Image {
     ///
     /// Errors that are encountered while retrieving image info will be returned
     /// ```
-    pub fn new(location: Location, name: &str) -> io::Result<Self> {
-        let json = match location {
-            Location::Local => lxc_output(&["image", "list", name, "--format", "json"])?,
-            Location::Remote(remote) => lxc_output(&["image", "list", &format!("{}:", remote), name, "--format", "json"])?
+    pub fn new(location: Location, name: &str) -> Result<Self> {
+        let json = match location.remote() {
+            None => lxc_output(&location.args(&["image", "list", name, "--format", "json"]))?,
+            Some(remote) => lxc_output(&location.args(&["image", "list", &format!("{}:", remote), name, "--format", "json"]))?
         };
 
         match serde_json::from_slice::<Vec<Self>>(&json) {
             Ok(mut list) => if list.len() == 1 {
                 Ok(list.remove(0))
             } else {
-                Err(io::Error::new(
-                    io::ErrorKind::NotFound,
+                Err(Error::NotFound(
                     format!("LXD image: {} not found", name)
                 ))
             },
             Err(err) => {
-                Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("LXD image: failed to parse json: {}", err)
-                ))
+                Err(Error::Json(err))
             }
         }
     }
+
+    /// Import an image from a unified tarball
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host to import to
+    /// * `path` - The path of the tarball
+    /// * `alias` - An alias to give the image
+    ///
+    /// # Return
+    ///
+    /// The LXD image information of the imported image
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while importing the image will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    /// use std::path::Path;
+    ///
+    /// let image = Image::import(Location::Local, Path::new("/tmp/image.tar.gz"), Some("test-import")).unwrap();
+    /// ```
+    pub fn import(location: Location, path: &Path, alias: Option<&str>) -> Result<Self> {
+        Self::import_files(location, &[path], alias)
+    }
+
+    /// Import an image from a split metadata tarball and root filesystem
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host to import to
+    /// * `metadata` - The path of the metadata tarball
+    /// * `rootfs` - The path of the root filesystem tarball or squashfs
+    /// * `alias` - An alias to give the image
+    ///
+    /// # Return
+    ///
+    /// The LXD image information of the imported image
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while importing the image will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    /// use std::path::Path;
+    ///
+    /// let image = Image::import_split(Location::Local, Path::new("/tmp/meta.tar.xz"), Path::new("/tmp/rootfs.squashfs"), None).unwrap();
+    /// ```
+    pub fn import_split(location: Location, metadata: &Path, rootfs: &Path, alias: Option<&str>) -> Result<Self> {
+        Self::import_files(location, &[metadata, rootfs], alias)
+    }
+
+    fn import_files(location: Location, paths: &[&Path], alias: Option<&str>) -> Result<Self> {
+        for path in paths.iter() {
+            check_readable(path)?;
+        }
+
+        let paths: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+        let mut args = location.args(&["image", "import"]);
+        args.extend(paths.iter().map(|path| path.as_str()));
+        let remote = location.remote().map(|remote| format!("{}:", remote));
+        if let Some(ref remote) = remote {
+            args.push(remote);
+        }
+        if let Some(alias) = alias {
+            args.extend_from_slice(&["--alias", alias]);
+        }
+
+        // lxc reports the fingerprint as `Image imported with fingerprint: ...`
+        let output = lxc_output(&args)?;
+        let output = String::from_utf8_lossy(&output);
+        let fingerprint = output.lines().find_map(|line| {
+            line.rsplit_once("fingerprint:").map(|(_, fingerprint)| fingerprint.trim())
+        }).ok_or_else(|| Error::Other(
+            format!("LXD image: failed to find fingerprint in {:?}", output.trim())
+        ))?;
+
+        Self::new(location, fingerprint)
+    }
+
+    /// Delete all images that no container or snapshot was created from
+    ///
+    /// Images that fail to delete are skipped, so that one failure does not
+    /// prevent the rest from being pruned.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    ///
+    /// # Return
+    ///
+    /// The fingerprints of the deleted images
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while listing images and containers will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    ///
+    /// let removed = Image::prune_unused(Location::Local).unwrap();
+    /// ```
+    pub fn prune_unused(location: Location) -> Result<Vec<String>> {
+        let inventory = Inventory::collect(location.clone())?;
+
+        let mut removed = Vec::new();
+        for image in inventory.unused_images() {
+            if image.delete(location.clone()).is_ok() {
+                removed.push(image.fingerprint.clone());
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Delete the image
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the image is on
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while deleting the image will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    ///
+    /// let image = Image::new(Location::Local, "test-publish").unwrap();
+    /// image.delete(Location::Local).unwrap();
+    /// ```
+    pub fn delete(&self, location: Location) -> Result<()> {
+        let name = match location.remote() {
+            None => self.fingerprint.clone(),
+            Some(remote) => format!("{}:{}", remote, self.fingerprint)
+        };
+        lxc(&location.args(&["image", "delete", &name]))
+    }
+
+    /// Export the image to files in a directory
+    ///
+    /// Unified images are written as a single tarball, while split images are
+    /// written as a metadata tarball and a root filesystem.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the image is on
+    /// * `dest` - The directory to write the files to, which must exist
+    ///
+    /// # Return
+    ///
+    /// The paths of the files written, sorted by name
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while exporting the image will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    /// use std::path::Path;
+    ///
+    /// let image = Image::new(Location::Local, "test-publish").unwrap();
+    /// for path in image.export(Location::Local, Path::new("/tmp")).unwrap() {
+    ///     println!("exported {}", path.display());
+    /// }
+    /// ```
+    pub fn export(&self, location: Location, dest: &Path) -> Result<Vec<PathBuf>> {
+        self.export_with_progress(location, dest, |_| ())
+    }
+
+    /// Export the image to files in a directory, reporting progress
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the image is on
+    /// * `dest` - The directory to write the files to, which must exist
+    /// * `progress` - Called with the percentage complete as the export proceeds
+    ///
+    /// # Return
+    ///
+    /// The paths of the files written, sorted by name
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while exporting the image will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    /// use std::path::Path;
+    ///
+    /// let image = Image::new(Location::Local, "test-publish").unwrap();
+    /// image.export_with_progress(Location::Local, Path::new("/tmp"), |percent| {
+    ///     println!("{}%", percent);
+    /// }).unwrap();
+    /// ```
+    pub fn export_with_progress<F: FnMut(u8)>(&self, location: Location, dest: &Path, progress: F) -> Result<Vec<PathBuf>> {
+        if !dest.is_dir() {
+            return Err(Error::InvalidInput(
+                format!("LXD image: {} is not a directory", dest.display())
+            ));
+        }
+
+        let name = match location.remote() {
+            None => self.fingerprint.clone(),
+            Some(remote) => format!("{}:{}", remote, self.fingerprint)
+        };
+        let dir = dest.display().to_string();
+        lxc_progress(&location.args(&["image", "export", &name, &dir]), progress)?;
+
+        // Given a directory, lxc names the files after the fingerprint, with a
+        // prefix and an extension that depend on the format of the image
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dest)? {
+            let path = entry?.path();
+            let is_export = path.file_name().is_some_and(|name| {
+                name.to_string_lossy().contains(&self.fingerprint)
+            });
+            if is_export && path.is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Add an alias for the image
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the image is on
+    /// * `alias` - The name of the new alias
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating the alias will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    ///
+    /// let image = Image::new(Location::Local, "test-publish").unwrap();
+    /// image.add_alias(Location::Local, "myapp/latest").unwrap();
+    /// ```
+    pub fn add_alias(&self, location: Location, alias: &str) -> Result<()> {
+        let alias = match location.remote() {
+            None => alias.to_string(),
+            Some(remote) => format!("{}:{}", remote, alias)
+        };
+        lxc(&location.args(&["image", "alias", "create", &alias, &self.fingerprint]))
+    }
+
+    /// Remove an alias of the image
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the image is on
+    /// * `alias` - The name of the alias
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while deleting the alias will be returned,
+    /// or an error if the alias does not belong to the image
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    ///
+    /// let image = Image::new(Location::Local, "myapp/latest").unwrap();
+    /// image.remove_alias(Location::Local, "myapp/latest").unwrap();
+    /// ```
+    pub fn remove_alias(&self, location: Location, alias: &str) -> Result<()> {
+        self.check_alias(alias)?;
+
+        let alias = match location.remote() {
+            None => alias.to_string(),
+            Some(remote) => format!("{}:{}", remote, alias)
+        };
+        lxc(&location.args(&["image", "alias", "delete", &alias]))
+    }
+
+    /// Rename an alias of the image
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the image is on
+    /// * `alias` - The current name of the alias
+    /// * `new_alias` - The new name of the alias
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while renaming the alias will be returned,
+    /// or an error if the alias does not belong to the image
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    ///
+    /// let image = Image::new(Location::Local, "myapp/latest").unwrap();
+    /// image.rename_alias(Location::Local, "myapp/latest", "myapp/previous").unwrap();
+    /// ```
+    pub fn rename_alias(&self, location: Location, alias: &str, new_alias: &str) -> Result<()> {
+        self.check_alias(alias)?;
+
+        let alias = match location.remote() {
+            None => alias.to_string(),
+            Some(remote) => format!("{}:{}", remote, alias)
+        };
+        lxc(&location.args(&["image", "alias", "rename", &alias, new_alias]))
+    }
+
+    /// Check that an alias belongs to the image, so that an alias that has
+    /// since been moved to another image is not changed
+    fn check_alias(&self, alias: &str) -> Result<()> {
+        if self.aliases.iter().any(|existing| existing.name == alias) {
+            Ok(())
+        } else {
+            Err(Error::NotFound(
+                format!("LXD image: {} is not an alias of {}", alias, self.fingerprint)
+            ))
+        }
+    }
+
+    /// Get the names of the aliases of the image
+    pub fn alias_names(&self) -> Vec<String> {
+        self.aliases.iter().map(|alias| alias.name.clone()).collect()
+    }
+
+    /// Copy an LXD image to another host, reporting progress
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The location of the host the image is on
+    /// * `dest` - The location of the host to copy the image to
+    /// * `progress` - Called with the percentage complete as the copy proceeds
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while copying will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Location};
+    ///
+    /// let image = Image::new(Location::Remote("ubuntu".to_string()), "16.04").unwrap();
+    /// image.copy(Location::Remote("ubuntu".to_string()), Location::Local, |percent| {
+    ///     println!("{}%", percent);
+    /// }).unwrap();
+    /// ```
+    pub fn copy<F: FnMut(u8)>(&self, source: Location, dest: Location, progress: F) -> Result<()> {
+        let source_name = source.prefix(&self.fingerprint);
+        let dest_name = format!("{}:", dest.remote().unwrap_or("local"));
+
+        let mut args = source.args(&["image", "copy", &source_name, &dest_name]);
+        args.extend(source.target_args(&dest));
+
+        lxc_progress(&args, progress).map(|_| ())
+    }
 }
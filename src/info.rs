@@ -1,15 +1,19 @@
-use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
-use std::io;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+use std::result;
 
-use super::{lxc_output, Location};
+#[cfg(feature = "socket")]
+use super::socket;
+use super::{lxc_output, Container, Error, Location, Result, Status};
 
-#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 pub struct Snapshot {
     pub architecture: String,
     pub config: BTreeMap<String, String>,
     pub created_at: String,
-    //pub devices: TODO,
+    #[serde(default)]
+    pub devices: BTreeMap<String, BTreeMap<String, String>>,
     pub ephemeral: bool,
     pub expanded_config: BTreeMap<String, String>,
     pub expanded_devices: BTreeMap<String, BTreeMap<String, String>>,
@@ -19,19 +23,152 @@ pub struct Snapshot {
     pub stateful: bool,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+impl Snapshot {
+    /// Compare the config of the snapshot with a live container
+    ///
+    /// Keys starting with `volatile.` are maintained by LXD and ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `live` - The current information of the container
+    ///
+    /// # Return
+    ///
+    /// Each key that differs, with its value in the snapshot and in the live
+    /// container, or `None` where the key is not set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Info;
+    ///
+    /// let mut info = Info::default();
+    /// info.config.insert("limits.cpu".to_string(), "4".to_string());
+    /// info.snapshots = Some(vec![Default::default()]);
+    ///
+    /// let mut snapshot = info.snapshots.clone().unwrap().remove(0);
+    /// snapshot.config.insert("limits.cpu".to_string(), "2".to_string());
+    /// snapshot.config.insert("security.nesting".to_string(), "true".to_string());
+    ///
+    /// assert_eq!(snapshot.diff_config(&info), vec![
+    ///     ("limits.cpu".to_string(), Some("2".to_string()), Some("4".to_string())),
+    ///     ("security.nesting".to_string(), Some("true".to_string()), None),
+    /// ]);
+    /// ```
+    pub fn diff_config(&self, live: &Info) -> Vec<(String, Option<String>, Option<String>)> {
+        let keys: BTreeSet<&String> = self.config.keys().chain(live.config.keys()).collect();
+
+        keys.into_iter().filter(|key| !key.starts_with("volatile.")).filter_map(|key| {
+            let old = self.config.get(key);
+            let new = live.config.get(key);
+            if old == new {
+                None
+            } else {
+                Some((key.clone(), old.cloned(), new.cloned()))
+            }
+        }).collect()
+    }
+}
+
+/// Deserialize a value that LXD may send as `null`, using the default instead
+fn null_default<'de, D: Deserializer<'de>, T: Default + Deserialize<'de>>(deserializer: D) -> result::Result<T, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+/// The disk usage of a volume of a container
+pub struct DiskUsage {
+    /// The number of bytes used
+    pub usage: u64,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+/// An address of a network interface in a container
+pub struct NetworkAddress {
+    /// `inet` or `inet6`
+    pub family: String,
+    pub address: String,
+    pub netmask: String,
+    /// `global`, `link` or `local`
+    pub scope: String,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+/// Traffic counters of a network interface in a container
+pub struct NetworkCounters {
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+    pub packets_received: u64,
+    pub packets_sent: u64,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+/// A network interface in a container
+///
+/// # Example
+///
+/// ```
+/// use lxd::NetworkInterface;
+///
+/// let interface: NetworkInterface = serde_json::from_str(r#"{
+///     "addresses": [{"family": "inet", "address": "10.0.3.27", "netmask": "24", "scope": "global"}],
+///     "counters": {"bytes_received": 1024, "bytes_sent": 512, "packets_received": 8, "packets_sent": 4},
+///     "hwaddr": "00:16:3e:4b:a3:ea",
+///     "host_name": "veth4N2Q7P",
+///     "mtu": 1500,
+///     "state": "up",
+///     "type": "broadcast"
+/// }"#).unwrap();
+/// assert_eq!(interface.addresses[0].address, "10.0.3.27");
+/// assert_eq!(interface.counters.bytes_received, 1024);
+/// assert_eq!(interface.mtu, 1500);
+/// ```
+pub struct NetworkInterface {
+    pub addresses: Vec<NetworkAddress>,
+    #[serde(default)]
+    pub counters: NetworkCounters,
+    #[serde(default)]
+    pub hwaddr: String,
+    /// The name of the interface on the host
+    #[serde(default)]
+    pub host_name: String,
+    #[serde(default)]
+    pub mtu: u64,
+    /// `up` or `down`
+    #[serde(default)]
+    pub state: String,
+    #[serde(default, rename = "type")]
+    pub interface_type: String,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 pub struct State {
     pub status: String,
     pub status_code: usize,
-    //pub disk: TODO,
+    /// The disk usage of each volume, by device name, if the storage driver
+    /// reports it
+    #[serde(default, deserialize_with = "null_default")]
+    pub disk: BTreeMap<String, DiskUsage>,
     pub memory: BTreeMap<String, usize>,
-    //pub network: TODO,
+    #[serde(default)]
+    pub network: Option<BTreeMap<String, NetworkInterface>>,
     pub pid: usize,
     pub processes: usize,
     pub cpu: BTreeMap<String, usize>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+/// How the user and group IDs of a container map to the host
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum IdmapMode {
+    /// IDs are shared with the host, as the container is privileged
+    Privileged,
+    /// IDs are mapped to a range shared with other unprivileged containers
+    Shared,
+    /// IDs are mapped to a range used only by this container
+    Isolated,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 /// LXD container information
 pub struct Info {
     pub architecture: String,
@@ -54,6 +191,9 @@ pub struct Info {
 impl Info {
     /// Retrieve LXD container information from all containers
     ///
+    /// With the `socket` feature, the local host is queried through the LXD
+    /// unix socket instead of running `lxc`.
+    ///
     /// # Arguments
     ///
     /// * `location` - The location of the host
@@ -73,18 +213,48 @@ impl Info {
     ///
     /// let info = Info::all(Location::Local).unwrap();
     /// ```
-    pub fn all(location: Location) -> io::Result<Vec<Self>> {
-        let json = match location {
-            Location::Local => lxc_output(&["list", "--format", "json"])?,
-            Location::Remote(remote) => lxc_output(&["list", &format!("{}:", remote), "--format", "json"])?
+    pub fn all(location: Location) -> Result<Vec<Self>> {
+        #[cfg(feature = "socket")]
+        {
+            if location.remote().is_none() {
+                return socket::get("/1.0/instances?recursion=2", location.project());
+            }
+        }
+
+        let json = match location.remote() {
+            None => lxc_output(&location.args(&["list", "--format", "json"]))?,
+            Some(remote) => lxc_output(&location.args(&["list", &format!("{}:", remote), "--format", "json"]))?
         };
 
-        serde_json::from_slice::<Vec<Self>>(&json).map_err(|err| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("LXD info: failed to parse json: {}", err)
-            )
-        })
+        serde_json::from_slice::<Vec<Self>>(&json).map_err(Error::Json)
+    }
+
+    /// Retrieve LXD container information from all containers using a profile
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `profile` - The name of the profile
+    ///
+    /// # Return
+    ///
+    /// The LXD container information of containers that have the profile applied
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Info, Location};
+    ///
+    /// let info = Info::using_profile(Location::Local, "default").unwrap();
+    /// ```
+    pub fn using_profile(location: Location, profile: &str) -> Result<Vec<Self>> {
+        let mut list = Self::all(location)?;
+        list.retain(|info| info.profiles.iter().any(|p| p == profile));
+        Ok(list)
     }
 
     /// Retrieve LXD container information from one container
@@ -110,27 +280,301 @@ impl Info {
     /// let mut container = Container::new(Location::Local, "test-info", "ubuntu:16.04").unwrap();
     /// let info = Info::new(Location::Local, "test-info").unwrap();
     /// ```
-    pub fn new(location: Location, name: &str) -> io::Result<Self> {
-        let json = match location {
-            Location::Local => lxc_output(&["list", &format!("{}$", name), "--format", "json"])?,
-            Location::Remote(remote) => lxc_output(&["list", &format!("{}:", remote), &format!("{}$", name), "--format", "json"])?
+    pub fn new(location: Location, name: &str) -> Result<Self> {
+        // The filter is an unanchored regex, so anchor it and escape the name,
+        // and compare names exactly in case it still matches others
+        let filter = format!("^{}$", regex_escape(name));
+        let json = match location.remote() {
+            None => lxc_output(&location.args(&["list", &filter, "--format", "json"]))?,
+            Some(remote) => lxc_output(&location.args(&["list", &format!("{}:", remote), &filter, "--format", "json"]))?
         };
 
-        match serde_json::from_slice::<Vec<Self>>(&json) {
-            Ok(mut list) => if list.len() == 1 {
-                Ok(list.remove(0))
-            } else {
-                Err(io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!("LXD info: {} not found", name)
-                ))
-            },
-            Err(err) => {
-                Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("LXD info: failed to parse json: {}", err)
-                ))
-            }
+        let list = serde_json::from_slice::<Vec<Self>>(&json).map_err(Error::Json)?;
+        list.into_iter().find(|info| info.name == name).ok_or_else(|| {
+            Error::NotFound(
+                format!("LXD info: {} not found", name)
+            )
+        })
+    }
+
+    /// Check if a container exists
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the container
+    ///
+    /// # Return
+    ///
+    /// True if the container exists, false if it does not
+    ///
+    /// # Errors
+    ///
+    /// Errors other than the container not being found will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Info, Location};
+    ///
+    /// if !Info::exists(Location::Local, "test-exists").unwrap() {
+    ///     println!("test-exists has not been created");
+    /// }
+    /// ```
+    pub fn exists(location: Location, name: &str) -> Result<bool> {
+        match Info::new(location, name) {
+            Ok(_) => Ok(true),
+            Err(Error::NotFound(_)) => Ok(false),
+            Err(err) => Err(err)
+        }
+    }
+
+    /// Get the status of the container as a typed status code
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Info, Location, Status};
+    ///
+    /// let container = Container::new(Location::Local, "test-status-enum", "ubuntu:16.04").unwrap();
+    /// let info = Info::new(Location::Local, "test-status-enum").unwrap();
+    /// assert_eq!(info.status_enum(), Status::Running);
+    /// ```
+    pub fn status_enum(&self) -> Status {
+        Status::from(self.status_code)
+    }
+
+    /// Get a handle to the container described by this information
+    ///
+    /// Nothing is launched, and like [`Container::attach`], the returned
+    /// handle does not own the container.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the information came from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Info, Location};
+    ///
+    /// let mut info = Info::default();
+    /// info.name = "web".to_string();
+    ///
+    /// assert_eq!(info.as_container(Location::Local).name(), "web");
+    /// assert_eq!(info.as_container(Location::Remote("production".to_string())).name(), "production:web");
+    /// ```
+    pub fn as_container(&self, location: Location) -> Container {
+        let mut container = Container::from_name(location, &self.name, false);
+        container.ephemeral = self.ephemeral;
+        container
+    }
+
+    /// Get the fingerprint of the image the container was created from
+    ///
+    /// # Return
+    ///
+    /// The value of `volatile.base_image`, or `None` if it is not set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Info;
+    ///
+    /// let mut info = Info::default();
+    /// assert_eq!(info.base_image(), None);
+    ///
+    /// info.expanded_config.insert("volatile.base_image".to_string(), "8f7f9b4a".to_string());
+    /// assert_eq!(info.base_image(), Some("8f7f9b4a".to_string()));
+    /// ```
+    pub fn base_image(&self) -> Option<String> {
+        self.config.get("volatile.base_image")
+            .or_else(|| self.expanded_config.get("volatile.base_image"))
+            .cloned()
+    }
+
+    /// Get the IP addresses of the container
+    ///
+    /// Loopback and link-local addresses are left out, as they cannot be used
+    /// to reach the container from the host.
+    ///
+    /// # Return
+    ///
+    /// The addresses of all interfaces, which is empty if the container is not
+    /// running
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Info, NetworkAddress, NetworkInterface};
+    /// use std::net::IpAddr;
+    ///
+    /// let address = |family: &str, address: &str, scope: &str| NetworkAddress {
+    ///     family: family.to_string(),
+    ///     address: address.to_string(),
+    ///     netmask: String::new(),
+    ///     scope: scope.to_string(),
+    /// };
+    ///
+    /// let mut info = Info::default();
+    /// info.state = Some(Default::default());
+    /// info.state.as_mut().unwrap().network = Some(vec![
+    ///     ("lo".to_string(), NetworkInterface {
+    ///         addresses: vec![address("inet", "127.0.0.1", "local")],
+    ///         ..Default::default()
+    ///     }),
+    ///     ("eth0".to_string(), NetworkInterface {
+    ///         addresses: vec![
+    ///             address("inet", "10.0.3.27", "global"),
+    ///             address("inet6", "fe80::216:3eff:fe4b:a3ea", "link"),
+    ///         ],
+    ///         ..Default::default()
+    ///     }),
+    /// ].into_iter().collect());
+    ///
+    /// let ipv4: Vec<IpAddr> = info.addresses().into_iter().filter(IpAddr::is_ipv4).collect();
+    /// assert_eq!(ipv4, vec!["10.0.3.27".parse::<IpAddr>().unwrap()]);
+    /// ```
+    pub fn addresses(&self) -> Vec<IpAddr> {
+        let network = self.state.as_ref().and_then(|state| state.network.as_ref());
+        network.into_iter().flat_map(|network| network.values()).flat_map(|interface| {
+            interface.addresses.iter()
+        }).filter(|address| address.scope != "link").filter_map(|address| {
+            address.address.parse::<IpAddr>().ok()
+        }).filter(|address| !address.is_loopback()).collect()
+    }
+
+    /// Get the host PID of the init process of the container
+    ///
+    /// # Return
+    ///
+    /// The PID, or `None` if the container is not running
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Info;
+    ///
+    /// let mut info = Info::default();
+    /// assert_eq!(info.init_pid(), None);
+    ///
+    /// info.state = Some(Default::default());
+    /// assert_eq!(info.init_pid(), None);
+    ///
+    /// info.state.as_mut().unwrap().pid = 1234;
+    /// assert_eq!(info.init_pid(), Some(1234));
+    /// ```
+    pub fn init_pid(&self) -> Option<u32> {
+        match self.state {
+            Some(ref state) if state.pid != 0 => Some(state.pid as u32),
+            _ => None
+        }
+    }
+
+    /// Get the autostart order of the container
+    ///
+    /// # Return
+    ///
+    /// The value of `boot.autostart.order`, or `None` if it is not set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Info;
+    ///
+    /// let mut info = Info::default();
+    /// assert_eq!(info.boot_order(), None);
+    ///
+    /// info.config.insert("boot.autostart.order".to_string(), "10".to_string());
+    /// assert_eq!(info.boot_order(), Some(10));
+    /// ```
+    pub fn boot_order(&self) -> Option<i32> {
+        self.config.get("boot.autostart.order")?.parse().ok()
+    }
+
+    /// Get the memory used by the container
+    ///
+    /// # Return
+    ///
+    /// The memory usage in bytes, or `None` if the container is not running
+    pub fn memory_usage(&self) -> Option<u64> {
+        self.state.as_ref()?.memory.get("usage").map(|&usage| usage as u64)
+    }
+
+    /// Check if a boolean key is enabled in the expanded config
+    fn config_enabled(&self, key: &str) -> bool {
+        matches!(self.expanded_config.get(key).map(|value| value.as_str()), Some("true" | "1" | "yes" | "on"))
+    }
+
+    /// Check if nesting is enabled, allowing containers inside the container
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Info;
+    ///
+    /// let mut info = Info::default();
+    /// assert!(!info.is_nested());
+    ///
+    /// info.expanded_config.insert("security.nesting".to_string(), "true".to_string());
+    /// assert!(info.is_nested());
+    /// ```
+    pub fn is_nested(&self) -> bool {
+        self.config_enabled("security.nesting")
+    }
+
+    /// Check if the container is privileged
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Info;
+    ///
+    /// let mut info = Info::default();
+    /// assert!(!info.is_privileged());
+    ///
+    /// info.expanded_config.insert("security.privileged".to_string(), "true".to_string());
+    /// assert!(info.is_privileged());
+    /// ```
+    pub fn is_privileged(&self) -> bool {
+        self.config_enabled("security.privileged")
+    }
+
+    /// Get how the user and group IDs of the container map to the host
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{IdmapMode, Info};
+    ///
+    /// let mut info = Info::default();
+    /// assert_eq!(info.idmap_mode(), IdmapMode::Shared);
+    ///
+    /// info.expanded_config.insert("security.idmap.isolated".to_string(), "true".to_string());
+    /// assert_eq!(info.idmap_mode(), IdmapMode::Isolated);
+    ///
+    /// info.expanded_config.insert("security.privileged".to_string(), "true".to_string());
+    /// assert_eq!(info.idmap_mode(), IdmapMode::Privileged);
+    /// ```
+    pub fn idmap_mode(&self) -> IdmapMode {
+        if self.is_privileged() {
+            IdmapMode::Privileged
+        } else if self.config_enabled("security.idmap.isolated") {
+            IdmapMode::Isolated
+        } else {
+            IdmapMode::Shared
+        }
+    }
+}
+
+/// Escape regex metacharacters in a name for an `lxc list` filter
+fn regex_escape(name: &str) -> String {
+    let mut escaped = String::new();
+    for c in name.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
         }
+        escaped.push(c);
     }
+    escaped
 }
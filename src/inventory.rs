@@ -0,0 +1,104 @@
+use std::collections::BTreeSet;
+
+use super::{Image, Info, Location, Result};
+
+/// The containers and images of a host, fetched together
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Inventory {
+    /// The containers, with their state and snapshots
+    pub containers: Vec<Info>,
+    /// The images
+    pub images: Vec<Image>,
+}
+
+impl Inventory {
+    /// Retrieve the containers and images of a host
+    ///
+    /// Only two `lxc` commands are run, as container information already
+    /// includes state and snapshots.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    ///
+    /// # Return
+    ///
+    /// The inventory of the host
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Inventory, Location};
+    ///
+    /// let inventory = Inventory::collect(Location::Local).unwrap();
+    /// println!("{} containers, {} images", inventory.containers.len(), inventory.images.len());
+    /// ```
+    pub fn collect(location: Location) -> Result<Self> {
+        Ok(Inventory {
+            containers: Info::all(location.clone())?,
+            images: Image::all(location)?,
+        })
+    }
+
+    /// Find a container by name
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Info, Inventory};
+    ///
+    /// let mut info = Info::default();
+    /// info.name = "web".to_string();
+    ///
+    /// let inventory = Inventory { containers: vec![info], images: Vec::new() };
+    /// assert!(inventory.container("web").is_some());
+    /// assert!(inventory.container("db").is_none());
+    /// ```
+    pub fn container(&self, name: &str) -> Option<&Info> {
+        self.containers.iter().find(|info| info.name == name)
+    }
+
+    /// Find an image by fingerprint, or by the name of one of its aliases
+    pub fn image(&self, name: &str) -> Option<&Image> {
+        self.images.iter().find(|image| {
+            image.fingerprint == name || image.aliases.iter().any(|alias| alias.name == name)
+        })
+    }
+
+    /// Get the images that no container or snapshot was created from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Image, Info, Inventory};
+    ///
+    /// let image: Image = serde_json::from_value(serde_json::json!({
+    ///     "auto_update": false, "properties": {}, "public": false, "aliases": [],
+    ///     "architecture": "x86_64", "cached": false, "filename": "", "fingerprint": "8f7f9b4a",
+    ///     "size": 0, "created_at": "", "expires_at": "", "last_used_at": "", "uploaded_at": ""
+    /// })).unwrap();
+    ///
+    /// let mut inventory = Inventory { containers: Vec::new(), images: vec![image] };
+    /// assert_eq!(inventory.unused_images().len(), 1);
+    ///
+    /// let mut info = Info::default();
+    /// info.config.insert("volatile.base_image".to_string(), "8f7f9b4a".to_string());
+    /// inventory.containers.push(info);
+    /// assert!(inventory.unused_images().is_empty());
+    /// ```
+    pub fn unused_images(&self) -> Vec<&Image> {
+        let mut used = BTreeSet::new();
+        for info in self.containers.iter() {
+            used.extend(info.base_image());
+            for snapshot in info.snapshots.iter().flatten() {
+                used.extend(snapshot.config.get("volatile.base_image").cloned());
+            }
+        }
+
+        self.images.iter().filter(|image| !used.contains(&image.fingerprint)).collect()
+    }
+}
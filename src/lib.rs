@@ -1,51 +1,393 @@
 //! A Rust library for controlling LXD
+//!
+//! LXD is controlled by running `lxc`, found on `PATH`. Set the `LXD_LXC_BIN`
+//! environment variable to run a different binary or wrapper script instead.
+//!
+//! Containers, images and profiles are in the `default` project, unless their
+//! location is in another project, see [`Location::in_project`].
 
-use std::process::{Command, Stdio};
-use std::io;
+use std::env;
+use std::ffi::OsString;
+use std::process::{self, Command, Stdio};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::thread;
+use std::time::{Duration, Instant};
 
+pub use batch::batch;
+pub use builder::{BaseImage, ContainerBuilder};
 pub use container::Container;
-pub use image::Image;
-pub use info::Info;
+pub use device::DeviceKind;
+pub use error::{Error, Result};
+pub use event::{events, Event, EventKind, Events};
+pub use exit_status::ExitStatus;
+pub use file::PushOptions;
+pub use image::{Alias, Image};
+pub use info::{DiskUsage, IdmapMode, Info, NetworkAddress, NetworkCounters, NetworkInterface};
+pub use inventory::Inventory;
+pub use limits::{IoLimit, IoLimits, MemoryLimit};
 pub use location::Location;
+pub use operation::Operation;
+pub use profile::Profile;
+pub use remote::{Remote, RemoteOptions};
+pub use snap::is_snap;
 pub use snapshot::Snapshot;
+pub use status::Status;
+pub use storage::StoragePool;
+pub use trust::Trust;
+pub use usage::{fleet_usage, FleetUsage};
 
+mod batch;
+mod builder;
 mod container;
+mod device;
+mod error;
+mod event;
+mod exit_status;
+mod file;
 mod image;
 mod info;
+mod inventory;
+mod limits;
 mod location;
+mod operation;
+mod profile;
+mod pty;
+mod remote;
+mod snap;
 mod snapshot;
+#[cfg(feature = "socket")]
+mod socket;
+mod status;
+mod storage;
+mod trust;
+mod units;
+mod usage;
 
-fn lxc(args: &[&str]) -> io::Result<()> {
-    let mut cmd = Command::new("lxc");
-    for arg in args.iter() {
-        cmd.arg(arg);
+/// Get the shell command line that runs `lxc` with the given arguments
+///
+/// The command starts with the binary from `LXD_LXC_BIN` if it is set, and
+/// arguments are quoted where needed, so the result can be pasted into a
+/// shell to reproduce the command.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(lxd::command_line(&["exec", "test", "--", "echo", "hello world"]), "lxc exec test -- echo 'hello world'");
+/// assert_eq!(lxd::command_line(&["exec", "test", "--", "echo", "it's"]), "lxc exec test -- echo 'it'\\''s'");
+/// ```
+pub fn command_line(args: &[&str]) -> String {
+    let bin = lxc_bin();
+    let bin = bin.to_string_lossy();
+
+    let mut line = String::new();
+    for arg in Some(&*bin).into_iter().chain(args.iter().copied()) {
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "%+,-./:=@_".contains(c)) {
+            line.push_str(arg);
+        } else {
+            line.push('\'');
+            line.push_str(&arg.replace('\'', "'\\''"));
+            line.push('\'');
+        }
     }
+    line
+}
+
+/// How long a terminated lxc process has to exit before it is killed
+const TERMINATE_GRACE: Duration = Duration::from_secs(5);
+
+/// Get the `lxc` binary to run, from `LXD_LXC_BIN` if it is set
+fn lxc_bin() -> OsString {
+    env::var_os("LXD_LXC_BIN").filter(|bin| !bin.is_empty()).unwrap_or_else(|| OsString::from("lxc"))
+}
 
-    let status = cmd.spawn()?.wait()?;
+/// Create a command that runs `lxc` with the given arguments
+fn lxc_command(args: &[&str]) -> Command {
+    let mut cmd = Command::new(lxc_bin());
+    cmd.args(args);
+    cmd
+}
+
+fn lxc(args: &[&str]) -> Result<()> {
+    let mut cmd = lxc_command(args);
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(Error::spawn)?;
+    let stderr = tee_stderr(&mut child)?;
+
+    let status = child.wait()?;
     if status.success() {
         Ok(())
     } else {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("LXD {:?} failed with {}", args, status)
-        ))
+        Err(Error::command_failed(args, status, &stderr))
     }
 }
 
-fn lxc_output(args: &[&str]) -> io::Result<Vec<u8>> {
-    let mut cmd = Command::new("lxc");
-    for arg in args.iter() {
-        cmd.arg(arg);
-    }
+fn lxc_status(args: &[&str]) -> Result<process::ExitStatus> {
+    let mut cmd = lxc_command(args);
+
+    Ok(cmd.spawn().map_err(Error::spawn)?.wait()?)
+}
+
+fn lxc_lines<F: FnMut(&str) -> bool>(args: &[&str], mut on_line: F) -> Result<process::ExitStatus> {
+    let mut cmd = lxc_command(args);
     cmd.stdout(Stdio::piped());
 
-    let output = cmd.spawn()?.wait_with_output()?;
+    let mut child = cmd.spawn().map_err(Error::spawn)?;
+    if let Some(stdout) = child.stdout.take() {
+        let mut reader = BufReader::new(stdout);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+
+            let text = String::from_utf8_lossy(&line);
+            if !on_line(text.trim_end_matches(['\r', '\n'])) {
+                return Ok(terminate(child)?);
+            }
+        }
+    }
+
+    Ok(child.wait()?)
+}
+
+/// Run `lxc`, terminating it if it has not exited before the timeout
+///
+/// Returns `None` if the process was terminated.
+fn lxc_timeout(args: &[&str], timeout: Duration) -> Result<Option<process::ExitStatus>> {
+    let mut cmd = lxc_command(args);
+
+    let start = Instant::now();
+    let mut child = cmd.spawn().map_err(Error::spawn)?;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+
+        if start.elapsed() >= timeout {
+            terminate(child)?;
+            return Ok(None);
+        }
+
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Stop a running lxc process and wait for it to exit
+///
+/// SIGTERM is sent first, as `lxc exec` forwards it to the command running
+/// in the container, which SIGKILL would leave running. If the process has
+/// not exited after a grace period, it is killed with SIGKILL.
+fn terminate(mut child: process::Child) -> io::Result<process::ExitStatus> {
+    // Safe as the child has not been waited on, so its pid cannot be reused
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+    }
+
+    let start = Instant::now();
+    while start.elapsed() < TERMINATE_GRACE {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    child.kill()?;
+    child.wait()
+}
+
+fn lxc_output(args: &[&str]) -> Result<Vec<u8>> {
+    lxc_capture(args).map(|output| output.stdout)
+}
+
+fn lxc_capture(args: &[&str]) -> Result<process::Output> {
+    let output = lxc_capture_status(args)?;
     if output.status.success() {
-        Ok(output.stdout)
+        Ok(output)
+    } else {
+        Err(Error::command_failed(args, output.status, &output.stderr))
+    }
+}
+
+/// Run `lxc` and capture its output, without failing if it exits unsuccessfully
+fn lxc_capture_status(args: &[&str]) -> Result<process::Output> {
+    let mut cmd = lxc_command(args);
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    Ok(cmd.spawn().map_err(Error::spawn)?.wait_with_output()?)
+}
+
+fn lxc_input(args: &[&str], input: &[u8]) -> Result<()> {
+    let mut cmd = lxc_command(args);
+    cmd.stdin(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(Error::spawn)?;
+
+    // Write stdin on another thread while stderr is drained, so that a
+    // command writing lots of errors before reading all of its input cannot
+    // deadlock. Stdin is closed when the writer finishes.
+    let stdin = child.stdin.take();
+    let (written, stderr) = thread::scope(|scope| {
+        let writer = scope.spawn(move || match stdin {
+            Some(mut stdin) => stdin.write_all(input),
+            None => Ok(()),
+        });
+        let stderr = tee_stderr(&mut child);
+        (writer.join().unwrap_or_else(|_| Err(io::Error::other("stdin writer panicked"))), stderr)
+    });
+    let stderr = stderr?;
+
+    // A command that exits without reading all of its input breaks the pipe,
+    // so report its failure before the write error
+    let status = child.wait()?;
+    if status.success() {
+        Ok(written?)
     } else {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("LXD {:?} failed with {}", args, output.status)
-        ))
+        Err(Error::command_failed(args, status, &stderr))
+    }
+}
+
+/// Copy the stderr of an lxc process to the stderr of this process, keeping
+/// a copy to report if the command fails
+fn tee_stderr(child: &mut process::Child) -> io::Result<Vec<u8>> {
+    let mut captured = Vec::new();
+    if let Some(mut stderr) = child.stderr.take() {
+        let mut buf = [0; 4096];
+        loop {
+            let count = stderr.read(&mut buf)?;
+            if count == 0 {
+                break;
+            }
+
+            // Losing the live copy is not worth failing the command over
+            let _ = io::stderr().write_all(&buf[..count]);
+            captured.extend_from_slice(&buf[..count]);
+        }
+    }
+    Ok(captured)
+}
+
+/// Run `lxc`, calling `progress` with each percentage it draws
+///
+/// Returns the lines of output that are not progress, such as warnings and
+/// completion messages, which are also reported if the command fails.
+fn lxc_progress<F: FnMut(u8)>(args: &[&str], mut progress: F) -> Result<String> {
+    // lxc only draws progress when stdout is a terminal, so give it one
+    let (mut master, slave) = pty::open()?;
+    let mut cmd = lxc_command(args);
+    cmd.stdout(Stdio::from(slave));
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(Error::spawn)?;
+    // Close this copy of the slave, so reading the master ends when lxc exits
+    drop(cmd);
+
+    let (stderr, output) = thread::scope(|scope| {
+        let stderr = scope.spawn(|| tee_stderr(&mut child));
+
+        let mut output = String::new();
+        let mut on_line = |text: &str| match parse_progress(text) {
+            Some(percent) => progress(percent),
+            None if !text.trim().is_empty() => {
+                output.push_str(text.trim_end());
+                output.push('\n');
+            },
+            None => ()
+        };
+
+        let mut line = Vec::new();
+        let mut buf = [0; 4096];
+        let read = loop {
+            let count = match master.read(&mut buf) {
+                Ok(0) => break Ok(()),
+                Ok(count) => count,
+                // Linux fails with EIO once all slaves have been closed
+                Err(err) if err.raw_os_error() == Some(libc::EIO) => break Ok(()),
+                Err(err) => break Err(err),
+            };
+            split_progress(&mut line, &buf[..count], &mut on_line);
+        };
+        if !line.is_empty() {
+            on_line(&String::from_utf8_lossy(&line));
+        }
+
+        let stderr = stderr.join().unwrap_or_else(|_| Err(io::Error::other("stderr reader panicked")));
+        read.and(stderr).map(|stderr| (stderr, output))
+    })?;
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(output)
+    } else {
+        // Some failures are only written to the terminal, so report both
+        let mut message = stderr;
+        message.extend_from_slice(output.as_bytes());
+        Err(Error::command_failed(args, status, &message))
+    }
+}
+
+/// Split output into lines as it arrives, keeping an unfinished line in
+/// `line` until the rest of it is read
+///
+/// Progress is redrawn in place with carriage returns, so both `\r` and `\n`
+/// end a line.
+fn split_progress<F: FnMut(&str)>(line: &mut Vec<u8>, bytes: &[u8], mut on_line: F) {
+    for &byte in bytes.iter() {
+        if byte == b'\r' || byte == b'\n' {
+            on_line(&String::from_utf8_lossy(line));
+            line.clear();
+        } else {
+            line.push(byte);
+        }
+    }
+}
+
+/// Parse a percentage out of an lxc progress line, such as
+/// `Copying the image: 45% (3.21MB/s)`
+fn parse_progress(line: &str) -> Option<u8> {
+    line.split_whitespace().find_map(|word| {
+        word.strip_suffix('%')?.parse::<u8>().ok()
+    }).map(|percent| percent.min(100))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_progress, split_progress};
+
+    #[test]
+    fn parse_progress_percent() {
+        assert_eq!(parse_progress("Transferring image: 42% (12.3MB/s)"), Some(42));
+        assert_eq!(parse_progress("Copying the image: 100% (3.21MB/s)"), Some(100));
+        assert_eq!(parse_progress("Copying the image: 255%"), Some(100));
+    }
+
+    #[test]
+    fn parse_progress_garbage() {
+        assert_eq!(parse_progress(""), None);
+        assert_eq!(parse_progress("Image copied successfully!"), None);
+        assert_eq!(parse_progress("Transferring image: abc% (12.3MB/s)"), None);
+        assert_eq!(parse_progress("Transferring image: -5%"), None);
+        assert_eq!(parse_progress("Transferring image: 300%"), None);
+        assert_eq!(parse_progress("%%% \u{fffd}\u{1b}[K"), None);
+    }
+
+    #[test]
+    fn split_progress_carriage_returns() {
+        let mut line = Vec::new();
+        let mut percents = Vec::new();
+        let mut on_line = |text: &str| percents.extend(parse_progress(text));
+
+        split_progress(&mut line, b"\rTransferring image: 10% (1.0MB/s)\rTransferring image: 2", &mut on_line);
+        split_progress(&mut line, b"0% (1.1MB/s)\rTransferring image: 30% (1.2MB/s)", &mut on_line);
+        split_progress(&mut line, b"\r\nImage copied successfully!\n", &mut on_line);
+
+        assert_eq!(percents, [10, 20, 30]);
+        assert!(line.is_empty());
     }
 }
@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use super::{Error, Result};
+use super::units::{format_bytes, parse_bytes};
+
+/// A read or write limit for a disk device
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IoLimit {
+    /// Bytes per second
+    Bandwidth(u64),
+    /// Operations per second
+    Iops(u64),
+}
+
+impl IoLimit {
+    /// Get the LXD config value of the limit
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::IoLimit;
+    ///
+    /// assert_eq!(IoLimit::Bandwidth(30 * 1024 * 1024).value(), "30MiB");
+    /// assert_eq!(IoLimit::Iops(200).value(), "200iops");
+    /// ```
+    pub fn value(&self) -> String {
+        match *self {
+            IoLimit::Bandwidth(bytes) => format_bytes(bytes),
+            IoLimit::Iops(iops) => format!("{}iops", iops),
+        }
+    }
+}
+
+impl FromStr for IoLimit {
+    type Err = Error;
+
+    /// Parse an LXD limit value, such as `30MB` or `200iops`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::IoLimit;
+    ///
+    /// assert_eq!("30MB".parse::<IoLimit>().unwrap(), IoLimit::Bandwidth(30000000));
+    /// assert_eq!("1GiB".parse::<IoLimit>().unwrap(), IoLimit::Bandwidth(1 << 30));
+    /// assert_eq!("200iops".parse::<IoLimit>().unwrap(), IoLimit::Iops(200));
+    /// assert!("fast".parse::<IoLimit>().is_err());
+    /// ```
+    fn from_str(value: &str) -> Result<Self> {
+        let limit = match value.trim().strip_suffix("iops") {
+            Some(iops) => iops.trim().parse::<u64>().ok().map(IoLimit::Iops),
+            None => parse_bytes(value).map(IoLimit::Bandwidth)
+        };
+
+        limit.ok_or_else(|| Error::InvalidInput(
+            format!("LXD limit: {:?} is not a valid IO limit", value)
+        ))
+    }
+}
+
+/// IO limits for a container and one of its disk devices
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IoLimits {
+    /// The value of `limits.disk.priority`, from 0 to 10
+    pub priority: Option<u8>,
+    /// The value of `limits.read` on the disk device
+    pub read: Option<IoLimit>,
+    /// The value of `limits.write` on the disk device
+    pub write: Option<IoLimit>,
+}
+
+/// A memory limit for a container
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MemoryLimit {
+    /// A number of bytes
+    Bytes(u64),
+    /// A percentage of the memory of the host
+    Percent(u8),
+}
+
+impl MemoryLimit {
+    /// Get the LXD config value of the limit
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::MemoryLimit;
+    ///
+    /// assert_eq!(MemoryLimit::Bytes(2 * 1024 * 1024 * 1024).value(), "2GiB");
+    /// assert_eq!(MemoryLimit::Percent(50).value(), "50%");
+    /// ```
+    pub fn value(&self) -> String {
+        match *self {
+            MemoryLimit::Bytes(bytes) => format_bytes(bytes),
+            MemoryLimit::Percent(percent) => format!("{}%", percent),
+        }
+    }
+}
+
+impl From<u64> for MemoryLimit {
+    fn from(bytes: u64) -> Self {
+        MemoryLimit::Bytes(bytes)
+    }
+}
+
+impl FromStr for MemoryLimit {
+    type Err = Error;
+
+    /// Parse an LXD memory limit value, such as `512MiB` or `50%`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::MemoryLimit;
+    ///
+    /// assert_eq!("512MiB".parse::<MemoryLimit>().unwrap(), MemoryLimit::Bytes(512 << 20));
+    /// assert_eq!("50%".parse::<MemoryLimit>().unwrap(), MemoryLimit::Percent(50));
+    /// assert!("150%".parse::<MemoryLimit>().is_err());
+    /// ```
+    fn from_str(value: &str) -> Result<Self> {
+        let limit = match value.trim().strip_suffix('%') {
+            Some(percent) => percent.trim().parse::<u8>().ok().filter(|&percent| percent <= 100).map(MemoryLimit::Percent),
+            None => parse_bytes(value).map(MemoryLimit::Bytes)
+        };
+
+        limit.ok_or_else(|| Error::InvalidInput(
+            format!("LXD limit: {:?} is not a valid memory limit", value)
+        ))
+    }
+}
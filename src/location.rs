@@ -1,8 +1,154 @@
+use std::str::FromStr;
+
+use super::{Error, Result};
+
 /// LXD host location
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Location {
     /// Local host
     Local,
     /// Remote host
     Remote(String),
+    /// A project on the local host, or on a remote host
+    ///
+    /// Adding this variant is a breaking change, as a `match` on `Location`
+    /// that lists every variant needs an arm for it.
+    Project {
+        /// The remote host, or `None` for the local host
+        remote: Option<String>,
+        /// The name of the project
+        project: String,
+    },
+}
+
+impl Location {
+    /// Split an `lxc` identifier such as `remote:container` into its location
+    /// and name
+    ///
+    /// Identifiers without a remote, or with the `local` remote, are on the
+    /// local host.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Location;
+    ///
+    /// assert_eq!(Location::split("production:web"), (Location::Remote("production".to_string()), "web".to_string()));
+    /// assert_eq!(Location::split("local:web"), (Location::Local, "web".to_string()));
+    /// assert_eq!(Location::split("web"), (Location::Local, "web".to_string()));
+    /// ```
+    pub fn split(spec: &str) -> (Location, String) {
+        match spec.split_once(':') {
+            Some((remote, name)) => (Location::from_remote(remote), name.to_string()),
+            None => (Location::Local, spec.to_string())
+        }
+    }
+
+    /// Use a project on the same host, instead of the default project
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - The name of the project
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Location;
+    ///
+    /// let location = Location::Remote("production".to_string()).in_project("tenant-a");
+    /// assert_eq!(location.remote(), Some("production"));
+    /// assert_eq!(location.project(), Some("tenant-a"));
+    /// assert_eq!(Location::Local.project(), None);
+    /// ```
+    pub fn in_project(self, project: &str) -> Location {
+        let remote = match self {
+            Location::Local => None,
+            Location::Remote(remote) => Some(remote),
+            Location::Project { remote, .. } => remote
+        };
+        Location::Project {
+            remote,
+            project: project.to_string(),
+        }
+    }
+
+    /// Get the remote of the host, or `None` for the local host
+    pub fn remote(&self) -> Option<&str> {
+        match *self {
+            Location::Local => None,
+            Location::Remote(ref remote) => Some(remote),
+            Location::Project { ref remote, .. } => remote.as_deref()
+        }
+    }
+
+    /// Get the project, or `None` for the default project
+    pub fn project(&self) -> Option<&str> {
+        match *self {
+            Location::Project { ref project, .. } => Some(project),
+            _ => None
+        }
+    }
+
+    /// Prefix a name with the remote of the host, as `lxc` identifies it
+    pub(crate) fn prefix(&self, name: &str) -> String {
+        match self.remote() {
+            Some(remote) => format!("{}:{}", remote, name),
+            None => name.to_string()
+        }
+    }
+
+    /// Get the `lxc` arguments that run a command in the project
+    pub(crate) fn args<'a>(&'a self, args: &[&'a str]) -> Vec<&'a str> {
+        let mut project_args = match self.project() {
+            Some(project) => vec!["--project", project],
+            None => Vec::new()
+        };
+        project_args.extend_from_slice(args);
+        project_args
+    }
+
+    /// Get the `lxc` arguments that copy or move to the project of `dest`,
+    /// when it is not the project of this location
+    pub(crate) fn target_args<'a>(&self, dest: &'a Location) -> Vec<&'a str> {
+        if self.project() == dest.project() {
+            Vec::new()
+        } else {
+            vec!["--target-project", dest.project().unwrap_or("default")]
+        }
+    }
+
+    fn from_remote(remote: &str) -> Location {
+        if remote.is_empty() || remote == "local" {
+            Location::Local
+        } else {
+            Location::Remote(remote.to_string())
+        }
+    }
+}
+
+impl FromStr for Location {
+    type Err = Error;
+
+    /// Parse the name of a remote, with or without a trailing `:`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Location;
+    ///
+    /// assert_eq!("".parse::<Location>().unwrap(), Location::Local);
+    /// assert_eq!("local".parse::<Location>().unwrap(), Location::Local);
+    /// assert_eq!("production:".parse::<Location>().unwrap(), Location::Remote("production".to_string()));
+    /// assert!("production:web".parse::<Location>().is_err());
+    /// ```
+    fn from_str(spec: &str) -> Result<Self> {
+        let remote = spec.strip_suffix(':').unwrap_or(spec);
+        if remote.contains(':') {
+            return Err(Error::InvalidInput(
+                format!("LXD location: {:?} is not a remote, use Location::split for a remote and name", spec)
+            ));
+        }
+
+        Ok(Location::from_remote(remote))
+    }
 }
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{lxc_output, Error, Location, Result, Status};
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+/// LXD background operation information
+pub struct Operation {
+    pub id: String,
+    pub class: String,
+    pub description: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub status: String,
+    pub status_code: usize,
+    #[serde(default)]
+    pub resources: Option<BTreeMap<String, Vec<String>>>,
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    pub may_cancel: bool,
+    pub err: String,
+    #[serde(default)]
+    pub location: String,
+}
+
+impl Operation {
+    /// Retrieve LXD operation information from all operations
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    ///
+    /// # Return
+    ///
+    /// The LXD operation information
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving operation info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Operation};
+    ///
+    /// let operations = Operation::all(Location::Local).unwrap();
+    /// ```
+    pub fn all(location: Location) -> Result<Vec<Self>> {
+        let json = match location.remote() {
+            None => lxc_output(&location.args(&["operation", "list", "--format", "json"]))?,
+            Some(remote) => lxc_output(&location.args(&["operation", "list", &format!("{}:", remote), "--format", "json"]))?
+        };
+
+        serde_json::from_slice::<Vec<Self>>(&json).map_err(Error::Json)
+    }
+
+    /// Retrieve LXD operation information from one operation
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `id` - The UUID of the operation
+    ///
+    /// # Return
+    ///
+    /// The LXD operation information
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving operation info will be returned
+    pub fn new(location: Location, id: &str) -> Result<Self> {
+        let json = match location.remote() {
+            None => lxc_output(&location.args(&["query", &format!("/1.0/operations/{}", id)])),
+            Some(remote) => lxc_output(&location.args(&["query", &format!("{}:/1.0/operations/{}", remote, id)]))
+        }.map_err(|err| match err {
+            // Only a missing operation is NotFound, which wait treats as
+            // finished, other failures are passed on as they are
+            Error::CommandFailed { ref stderr, .. } if stderr.to_lowercase().contains("not found") => Error::NotFound(
+                format!("LXD operation: {} not found: {}", id, err)
+            ),
+            err => err
+        })?;
+
+        serde_json::from_slice::<Self>(&json).map_err(Error::Json)
+    }
+
+    /// Get the status of the operation as a typed status code
+    pub fn status_enum(&self) -> Status {
+        Status::from(self.status_code)
+    }
+
+    /// Wait for the operation to complete, polling once a second
+    ///
+    /// LXD forgets about operations shortly after they finish, so an
+    /// operation that can no longer be found is treated as complete.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `timeout` - How long to wait before giving up, or `None` to wait forever
+    ///
+    /// # Return
+    ///
+    /// An empty tuple once the operation has succeeded
+    ///
+    /// # Errors
+    ///
+    /// Errors will be returned if the operation fails, is cancelled, or does
+    /// not complete before the timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Operation};
+    ///
+    /// for operation in Operation::all(Location::Local).unwrap() {
+    ///     operation.wait(Location::Local, None).unwrap();
+    /// }
+    /// ```
+    pub fn wait(&self, location: Location, timeout: Option<Duration>) -> Result<()> {
+        let start = Instant::now();
+        loop {
+            let operation = match Operation::new(location.clone(), &self.id) {
+                Ok(operation) => operation,
+                Err(Error::NotFound(_)) => return Ok(()),
+                Err(err) => return Err(err)
+            };
+
+            match operation.status_enum() {
+                Status::Success => return Ok(()),
+                Status::Failure | Status::Cancelled => return Err(Error::Other(
+                    format!("LXD operation: {} {}: {}", self.id, operation.status, operation.err)
+                )),
+                _ => ()
+            }
+
+            if let Some(timeout) = timeout {
+                if start.elapsed() >= timeout {
+                    return Err(Error::TimedOut(
+                        format!("LXD operation: {} did not complete in {:?}", self.id, timeout)
+                    ));
+                }
+            }
+
+            thread::sleep(Duration::from_secs(1));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{lxc, lxc_output, DeviceKind, Error, Location, Result};
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+/// LXD profile information
+pub struct Profile {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub config: BTreeMap<String, String>,
+    #[serde(default)]
+    pub devices: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(default)]
+    pub used_by: Vec<String>,
+}
+
+impl Profile {
+    /// Retrieve LXD profile information from all profiles
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    ///
+    /// # Return
+    ///
+    /// The LXD profile information
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving profile info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Profile};
+    ///
+    /// let profiles = Profile::all(Location::Local).unwrap();
+    /// ```
+    pub fn all(location: Location) -> Result<Vec<Self>> {
+        let json = match location.remote() {
+            None => lxc_output(&location.args(&["profile", "list", "--format", "json"]))?,
+            Some(remote) => lxc_output(&location.args(&["profile", "list", &format!("{}:", remote), "--format", "json"]))?
+        };
+
+        serde_json::from_slice::<Vec<Self>>(&json).map_err(Error::Json)
+    }
+
+    /// Retrieve LXD profile information from one profile
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the profile
+    ///
+    /// # Return
+    ///
+    /// The LXD profile information
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving profile info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Profile};
+    ///
+    /// let profile = Profile::new(Location::Local, "default").unwrap();
+    /// ```
+    pub fn new(location: Location, name: &str) -> Result<Self> {
+        Self::all(location)?.into_iter().find(|profile| profile.name == name).ok_or_else(|| {
+            Error::NotFound(
+                format!("LXD profile: {} not found", name)
+            )
+        })
+    }
+
+    /// Create an empty profile
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the new profile
+    ///
+    /// # Return
+    ///
+    /// The LXD profile information of the new profile
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating the profile will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Profile};
+    ///
+    /// let profile = Profile::create(Location::Local, "build").unwrap();
+    /// profile.set_config(Location::Local, "limits.cpu", "4").unwrap();
+    ///
+    /// let container = Container::builder(Location::Local, "test-profile-create", "ubuntu:16.04")
+    ///     .profile("default")
+    ///     .profile("build")
+    ///     .launch()
+    ///     .unwrap();
+    /// ```
+    pub fn create(location: Location, name: &str) -> Result<Self> {
+        let full_name = match location.remote() {
+            None => name.to_string(),
+            Some(remote) => format!("{}:{}", remote, name)
+        };
+        lxc(&location.args(&["profile", "create", &full_name]))?;
+
+        Profile::new(location, name)
+    }
+
+    /// Set a config key of the profile
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the profile is on
+    /// * `key` - The config key, `limits.cpu` for example
+    /// * `value` - The value of the config key
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while setting the config will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Profile};
+    ///
+    /// let profile = Profile::new(Location::Local, "build").unwrap();
+    /// profile.set_config(Location::Local, "limits.memory", "4GB").unwrap();
+    /// ```
+    pub fn set_config(&self, location: Location, key: &str, value: &str) -> Result<()> {
+        lxc(&location.args(&["profile", "set", &self.full_name(&location), key, value]))
+    }
+
+    /// Add a device to the profile, which is added to all containers using it
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the profile is on
+    /// * `name` - The name of the device
+    /// * `kind` - The type of the device
+    /// * `options` - The config keys and values of the device
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while adding the device will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{DeviceKind, Location, Profile};
+    ///
+    /// let profile = Profile::new(Location::Local, "build").unwrap();
+    /// profile.add_device(Location::Local, "cache", DeviceKind::Disk, &[("source", "/srv/cache"), ("path", "/cache")]).unwrap();
+    /// ```
+    pub fn add_device(&self, location: Location, name: &str, kind: DeviceKind, options: &[(&str, &str)]) -> Result<()> {
+        let full_name = self.full_name(&location);
+        let options: Vec<String> = options.iter().map(|(key, value)| {
+            format!("{}={}", key, value)
+        }).collect();
+
+        let mut args = location.args(&["profile", "device", "add", &full_name, name, kind.as_str()]);
+        for arg in options.iter() {
+            args.push(arg);
+        }
+        lxc(&args)
+    }
+
+    /// Delete the profile, which must not be used by any container
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the profile is on
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while deleting the profile will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Profile};
+    ///
+    /// let profile = Profile::new(Location::Local, "build").unwrap();
+    /// profile.delete(Location::Local).unwrap();
+    /// ```
+    pub fn delete(&self, location: Location) -> Result<()> {
+        lxc(&location.args(&["profile", "delete", &self.full_name(&location)]))
+    }
+
+    /// Get the name of the profile, prefixed with its remote
+    fn full_name(&self, location: &Location) -> String {
+        location.prefix(&self.name)
+    }
+}
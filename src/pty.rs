@@ -0,0 +1,45 @@
+use std::ffi::CStr;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::FromRawFd;
+
+/// Open a pseudo-terminal, returning its master and slave
+///
+/// The terminal is made wide, as lxc truncates progress lines to the width
+/// of the terminal.
+pub(crate) fn open() -> io::Result<(File, File)> {
+    // Safe as the descriptor is owned by `master` as soon as it is opened,
+    // and `name` is nul terminated by ptsname_r on success
+    unsafe {
+        let fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let master = File::from_raw_fd(fd);
+
+        if libc::grantpt(fd) != 0 || libc::unlockpt(fd) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut name = [0 as libc::c_char; 128];
+        let err = libc::ptsname_r(fd, name.as_mut_ptr(), name.len());
+        if err != 0 {
+            return Err(io::Error::from_raw_os_error(err));
+        }
+        let path = CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned();
+
+        let size = libc::winsize {
+            ws_row: 24,
+            ws_col: 240,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        if libc::ioctl(fd, libc::TIOCSWINSZ, &size) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let slave = OpenOptions::new().read(true).write(true).custom_flags(libc::O_NOCTTY).open(path)?;
+        Ok((master, slave))
+    }
+}
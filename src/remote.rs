@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{lxc, lxc_output, Error, Location, Result};
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// LXD remote information, from the `lxc` client config
+///
+/// # Example
+///
+/// ```
+/// use lxd::Remote;
+///
+/// let remote: Remote = serde_json::from_str(r#"{
+///     "Addr": "https://images.linuxcontainers.org",
+///     "AuthType": "",
+///     "Protocol": "simplestreams",
+///     "Public": true,
+///     "Static": false
+/// }"#).unwrap();
+/// assert_eq!(remote.protocol, "simplestreams");
+/// assert!(remote.public);
+/// ```
+pub struct Remote {
+    /// The name of the remote, which is not part of the remote config
+    #[serde(skip)]
+    pub name: String,
+    pub addr: String,
+    #[serde(default)]
+    pub auth_type: String,
+    #[serde(default)]
+    pub project: String,
+    pub protocol: String,
+    #[serde(default)]
+    pub public: bool,
+    /// True for remotes built into `lxc`, which cannot be removed
+    #[serde(default, rename = "Static")]
+    pub is_static: bool,
+}
+
+/// Options for adding an LXD remote
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RemoteOptions {
+    /// Accept the certificate of the remote without prompting
+    pub accept_certificate: bool,
+    /// The trust password of the remote
+    pub password: Option<String>,
+    /// The protocol of the remote, `lxd` or `simplestreams`
+    pub protocol: Option<String>,
+    /// Use the remote without authenticating
+    pub public: bool,
+}
+
+impl Remote {
+    /// Retrieve LXD remote information from all configured remotes
+    ///
+    /// # Return
+    ///
+    /// The LXD remote information, sorted by name
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving remote info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Remote;
+    ///
+    /// let remotes = Remote::all().unwrap();
+    /// ```
+    pub fn all() -> Result<Vec<Self>> {
+        let json = lxc_output(&["remote", "list", "--format", "json"])?;
+
+        let remotes = serde_json::from_slice::<BTreeMap<String, Self>>(&json).map_err(Error::Json)?;
+        Ok(remotes.into_iter().map(|(name, mut remote)| {
+            remote.name = name;
+            remote
+        }).collect())
+    }
+
+    /// Retrieve LXD remote information from one remote
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the remote
+    ///
+    /// # Return
+    ///
+    /// The LXD remote information
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving remote info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Remote;
+    ///
+    /// let remote = Remote::new("images").unwrap();
+    /// ```
+    pub fn new(name: &str) -> Result<Self> {
+        Self::all()?.into_iter().find(|remote| remote.name == name).ok_or_else(|| {
+            Error::NotFound(
+                format!("LXD remote: {} not found", name)
+            )
+        })
+    }
+
+    /// Add a remote
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the new remote
+    /// * `url` - The address of the remote, `https://lxd.example.com:8443` for example
+    /// * `options` - Options for authenticating with the remote
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while adding the remote will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Remote, RemoteOptions};
+    ///
+    /// Remote::add("production", "https://lxd.example.com:8443", &RemoteOptions {
+    ///     accept_certificate: true,
+    ///     password: Some("secret".to_string()),
+    ///     ..Default::default()
+    /// }).unwrap();
+    /// ```
+    pub fn add(name: &str, url: &str, options: &RemoteOptions) -> Result<()> {
+        let mut args = vec!["remote", "add", name, url];
+        if options.accept_certificate {
+            args.push("--accept-certificate");
+        }
+        let password = options.password.as_ref().map(|password| format!("--password={}", password));
+        if let Some(ref password) = password {
+            args.push(password);
+        }
+        if let Some(ref protocol) = options.protocol {
+            args.extend_from_slice(&["--protocol", protocol]);
+        }
+        if options.public {
+            args.push("--public");
+        }
+
+        lxc(&args).map_err(|err| match err {
+            // Keep the password out of the error message
+            Error::CommandFailed { status, stderr, .. } => Error::CommandFailed {
+                args: vec!["remote".to_string(), "add".to_string(), name.to_string(), url.to_string()],
+                status,
+                stderr,
+            },
+            err => err
+        })
+    }
+
+    /// Remove the remote
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while removing the remote will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Remote;
+    ///
+    /// Remote::new("production").unwrap().remove().unwrap();
+    /// ```
+    pub fn remove(&self) -> Result<()> {
+        lxc(&["remote", "remove", &self.name])
+    }
+
+    /// Get the location of the host the remote refers to
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Remote};
+    ///
+    /// let remote = Remote::new("production").unwrap();
+    /// assert_eq!(remote.location(), Location::Remote("production".to_string()));
+    /// ```
+    pub fn location(&self) -> Location {
+        Location::Remote(self.name.clone())
+    }
+}
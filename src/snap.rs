@@ -0,0 +1,68 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use super::{lxc_bin, Error, Result};
+
+/// Find the `lxc` binary, searching `PATH` unless it is given as a path
+fn lxc_path() -> Option<PathBuf> {
+    let bin = PathBuf::from(lxc_bin());
+    if bin.components().count() > 1 {
+        return Some(bin);
+    }
+
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths).map(|dir| dir.join(&bin)).find(|path| path.is_file())
+}
+
+/// Check if `lxc` is provided by the LXD snap
+///
+/// The snap is confined, so it can only read files from non-hidden paths in
+/// the home directory of the user.
+///
+/// # Example
+///
+/// ```
+/// if lxd::is_snap() {
+///     println!("lxc is confined by snap");
+/// }
+/// ```
+pub fn is_snap() -> bool {
+    match lxc_path() {
+        Some(path) => {
+            // /snap/bin/lxc is a symlink to /usr/bin/snap
+            path.starts_with("/snap") || fs::canonicalize(&path).map(|real| {
+                real.file_name().is_some_and(|name| name == "snap")
+            }).unwrap_or(false)
+        },
+        None => false
+    }
+}
+
+/// Check that a path on the host can be read by `lxc`
+pub(crate) fn check_readable(path: &Path) -> Result<()> {
+    if !is_snap() {
+        return Ok(());
+    }
+
+    let path = fs::canonicalize(path)?;
+    let readable = match env::var_os("HOME") {
+        Some(home) => path.strip_prefix(home).is_ok_and(|relative| {
+            !relative.components().any(|component| match component {
+                Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+                _ => false
+            })
+        }),
+        None => false
+    };
+
+    if readable {
+        Ok(())
+    } else {
+        Err(Error::Io(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("LXD snap: {} cannot be read by the snap confined lxc, move it to a non-hidden path in $HOME", path.display())
+        )))
+    }
+}
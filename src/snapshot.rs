@@ -1,11 +1,10 @@
-use std::io;
+use super::{lxc, Container, Error, Result};
 
-use super::{lxc, Container};
-
-/// An LXD ephemeral snapshot
+/// An LXD snapshot, which is deleted when dropped unless it is kept
 pub struct Snapshot<'a> {
-    _container: &'a Container,
-    name: String
+    container: &'a Container,
+    name: String,
+    keep: bool
 }
 
 impl<'a> Snapshot<'a> {
@@ -23,13 +22,82 @@ impl<'a> Snapshot<'a> {
     ///
     /// Errors that are encountered while creating snapshot will be returned
     /// ```
-    pub fn new(container: &'a Container, name: &str) -> io::Result<Snapshot<'a>> {
-        lxc(&["snapshot", container.name(), name])?;
+    pub fn new(container: &'a Container, name: &str) -> Result<Snapshot<'a>> {
+        Snapshot::create(container, name, &[])
+    }
+
+    /// Create a snapshot of a container, replacing any snapshot with the same name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the new snapshot
+    ///
+    /// # Return
+    ///
+    /// A new snapshot on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating snapshot will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Snapshot};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-reuse", "ubuntu:16.04").unwrap();
+    /// let snapshot = Snapshot::new_reuse(&container, "latest").unwrap();
+    /// ```
+    pub fn new_reuse(container: &'a Container, name: &str) -> Result<Snapshot<'a>> {
+        Snapshot::create(container, name, &["--reuse"])
+    }
+
+    /// Create a snapshot of a running container, including its memory state
+    ///
+    /// Stateful snapshots are taken with CRIU, which has to be installed on
+    /// the host.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the new snapshot
+    ///
+    /// # Return
+    ///
+    /// A new snapshot on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating snapshot will be returned,
+    /// with `Error::Unsupported` if CRIU is not available
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Snapshot};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-stateful", "ubuntu:16.04").unwrap();
+    /// let snapshot = Snapshot::new_stateful(&container, "running").unwrap();
+    /// snapshot.restore(true).unwrap();
+    /// ```
+    pub fn new_stateful(container: &'a Container, name: &str) -> Result<Snapshot<'a>> {
+        Snapshot::create(container, name, &["--stateful"]).map_err(|err| match err {
+            Error::CommandFailed { ref stderr, .. } if stderr.to_lowercase().contains("criu") => Error::Unsupported(
+                format!("LXD snapshot: stateful snapshots require CRIU on the host: {}", stderr)
+            ),
+            err => err
+        })
+    }
+
+    fn create(container: &'a Container, name: &str, flags: &[&str]) -> Result<Snapshot<'a>> {
+        let mut args = container.args(&["snapshot", container.name(), name]);
+        args.extend_from_slice(flags);
+        lxc(&args)?;
 
         let full_name = format!("{}/{}", container.name(), name);
         Ok(Snapshot {
-            _container: container,
-            name: full_name
+            container,
+            name: full_name,
+            keep: false
         })
     }
 
@@ -56,13 +124,167 @@ impl<'a> Snapshot<'a> {
     /// let snapshot = Snapshot::new(&container, "test-snapshot-publish").unwrap();
     /// snapshot.publish("test-publish").unwrap();
     /// ```
-    pub fn publish(&self, alias: &str) -> io::Result<()> {
-        lxc(&["publish", &self.name, "--alias", alias])
+    pub fn publish(&self, alias: &str) -> Result<()> {
+        self.publish_with(alias, &[], None)
+    }
+
+    /// Publish snapshot as an image, with image properties and a choice of
+    /// compression
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - alias of the new image
+    /// * `properties` - properties of the new image, such as `description`,
+    ///   `os` and `release`
+    /// * `compression` - compression algorithm of the image, such as `none`
+    ///   or `xz`, or `None` for the default of the host
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while publishing will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Image, Location, Snapshot};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-publish-with", "ubuntu:16.04").unwrap();
+    /// let snapshot = Snapshot::new(&container, "release").unwrap();
+    /// snapshot.publish_with("test-publish-with", &[("os", "ubuntu"), ("release", "xenial")], Some("none")).unwrap();
+    ///
+    /// let image = Image::new(Location::Local, "test-publish-with").unwrap();
+    /// assert_eq!(image.properties["release"], "xenial");
+    /// ```
+    pub fn publish_with(&self, alias: &str, properties: &[(&str, &str)], compression: Option<&str>) -> Result<()> {
+        let properties: Vec<String> = properties.iter().map(|(key, value)| {
+            format!("{}={}", key, value)
+        }).collect();
+
+        let mut args = self.container.args(&["publish", &self.name, "--alias", alias]);
+        if let Some(compression) = compression {
+            args.extend_from_slice(&["--compression", compression]);
+        }
+        for arg in properties.iter() {
+            args.push(arg);
+        }
+        lxc(&args)
+    }
+
+    /// Keep the snapshot after the handle is dropped
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Snapshot};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-keep", "ubuntu:16.04").unwrap();
+    /// Snapshot::new(&container, "backup").unwrap().keep();
+    /// ```
+    pub fn keep(mut self) {
+        self.keep = true;
+    }
+
+    /// Rename the snapshot
+    ///
+    /// # Arguments
+    ///
+    /// * `new_name` - The new name of the snapshot
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while renaming will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Snapshot};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-rename", "ubuntu:16.04").unwrap();
+    /// let mut snapshot = Snapshot::new(&container, "daily-0").unwrap();
+    /// snapshot.rename("daily-1").unwrap();
+    /// ```
+    pub fn rename(&mut self, new_name: &str) -> Result<()> {
+        let full_name = format!("{}/{}", self.container.name(), new_name);
+        lxc(&self.container.args(&["rename", &self.name, &full_name]))?;
+
+        self.name = full_name;
+        Ok(())
+    }
+
+    /// Delete the snapshot now, rather than when the handle is dropped
+    ///
+    /// Kept snapshots are deleted as well.
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while deleting will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Snapshot};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-delete", "ubuntu:16.04").unwrap();
+    /// let snapshot = Snapshot::new(&container, "temporary").unwrap();
+    /// snapshot.delete().unwrap();
+    /// ```
+    pub fn delete(mut self) -> Result<()> {
+        // Don't try again when dropped if this fails
+        self.keep = true;
+        lxc(&self.container.args(&["delete", &self.name]))
+    }
+
+    /// Restore the container to the snapshot
+    ///
+    /// # Arguments
+    ///
+    /// * `stateful` - True to also restore the running state of a stateful
+    ///   snapshot
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while restoring will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Container, Location, Snapshot};
+    ///
+    /// let container = Container::new(Location::Local, "test-snapshot-restore", "ubuntu:16.04").unwrap();
+    /// let snapshot = Snapshot::new(&container, "clean").unwrap();
+    /// snapshot.restore(false).unwrap();
+    /// ```
+    pub fn restore(&self, stateful: bool) -> Result<()> {
+        let (_, snapshot) = self.name.rsplit_once('/').unwrap_or(("", &self.name));
+
+        let mut args = self.container.args(&["restore", self.container.name(), snapshot]);
+        if stateful {
+            args.push("--stateful");
+        }
+        lxc(&args)
     }
 }
 
 impl<'a> Drop for Snapshot<'a> {
     fn drop(&mut self) {
-        let _ = lxc(&["delete", &self.name]);
+        if !self.keep {
+            let _ = lxc(&self.container.args(&["delete", &self.name]));
+        }
     }
 }
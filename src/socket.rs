@@ -0,0 +1,63 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::env;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use super::{Error, Result};
+
+#[derive(Deserialize)]
+struct Response<T> {
+    #[serde(default)]
+    error: String,
+    metadata: Option<T>,
+}
+
+/// Find the unix socket of the local LXD daemon
+fn socket_path() -> PathBuf {
+    if let Some(dir) = env::var_os("LXD_DIR") {
+        return PathBuf::from(dir).join("unix.socket");
+    }
+
+    let snap = PathBuf::from("/var/snap/lxd/common/lxd/unix.socket");
+    if snap.exists() {
+        snap
+    } else {
+        PathBuf::from("/var/lib/lxd/unix.socket")
+    }
+}
+
+/// Send a GET request to the local LXD REST API and parse the returned metadata
+pub(crate) fn get<T: DeserializeOwned>(path: &str, project: Option<&str>) -> Result<T> {
+    let path = match project {
+        Some(project) => {
+            let separator = if path.contains('?') { '&' } else { '?' };
+            format!("{}{}project={}", path, separator, project)
+        },
+        None => path.to_string()
+    };
+
+    let mut stream = UnixStream::connect(socket_path())?;
+
+    // HTTP/1.0 makes the daemon close the connection after responding,
+    // without using chunked encoding
+    write!(stream, "GET {} HTTP/1.0\r\nHost: lxd\r\n\r\n", path)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+
+    let body = response.windows(4).position(|window| window == b"\r\n\r\n").map(|i| &response[i + 4..]).ok_or_else(|| {
+        Error::Other(
+            format!("LXD socket: {} returned an invalid response", path)
+        )
+    })?;
+
+    let response = serde_json::from_slice::<Response<T>>(body).map_err(Error::Json)?;
+
+    response.metadata.ok_or_else(|| {
+        Error::Other(
+            format!("LXD socket: {} failed: {}", path, response.error)
+        )
+    })
+}
@@ -0,0 +1,115 @@
+/// LXD status code, used by containers and operations
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Status {
+    /// Operation created
+    OperationCreated,
+    /// Started
+    Started,
+    /// Stopped
+    Stopped,
+    /// Running
+    Running,
+    /// Cancelling
+    Cancelling,
+    /// Pending
+    Pending,
+    /// Starting
+    Starting,
+    /// Stopping
+    Stopping,
+    /// Aborting
+    Aborting,
+    /// Freezing
+    Freezing,
+    /// Frozen
+    Frozen,
+    /// Thawed
+    Thawed,
+    /// Error
+    Error,
+    /// Ready
+    Ready,
+    /// Success
+    Success,
+    /// Failure
+    Failure,
+    /// Cancelled
+    Cancelled,
+    /// A code not known to this library
+    Unknown(usize),
+    /// A status not known to this library, as LXD described it
+    Other(String),
+}
+
+impl Status {
+    /// Get the numeric LXD status code, or 0 if it is not known
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Status;
+    ///
+    /// assert_eq!(Status::Running.code(), 103);
+    /// assert_eq!(Status::Unknown(999).code(), 999);
+    /// assert_eq!(Status::Other("Migrating".to_string()).code(), 0);
+    /// ```
+    pub fn code(&self) -> usize {
+        match *self {
+            Status::OperationCreated => 100,
+            Status::Started => 101,
+            Status::Stopped => 102,
+            Status::Running => 103,
+            Status::Cancelling => 104,
+            Status::Pending => 105,
+            Status::Starting => 106,
+            Status::Stopping => 107,
+            Status::Aborting => 108,
+            Status::Freezing => 109,
+            Status::Frozen => 110,
+            Status::Thawed => 111,
+            Status::Error => 112,
+            Status::Ready => 113,
+            Status::Success => 200,
+            Status::Failure => 400,
+            Status::Cancelled => 401,
+            Status::Unknown(code) => code,
+            Status::Other(_) => 0,
+        }
+    }
+}
+
+impl From<usize> for Status {
+    /// Convert a numeric LXD status code
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::Status;
+    ///
+    /// assert_eq!(Status::from(102), Status::Stopped);
+    /// assert_eq!(Status::from(110), Status::Frozen);
+    /// assert_eq!(Status::from(999), Status::Unknown(999));
+    /// ```
+    fn from(code: usize) -> Self {
+        match code {
+            100 => Status::OperationCreated,
+            101 => Status::Started,
+            102 => Status::Stopped,
+            103 => Status::Running,
+            104 => Status::Cancelling,
+            105 => Status::Pending,
+            106 => Status::Starting,
+            107 => Status::Stopping,
+            108 => Status::Aborting,
+            109 => Status::Freezing,
+            110 => Status::Frozen,
+            111 => Status::Thawed,
+            112 => Status::Error,
+            113 => Status::Ready,
+            200 => Status::Success,
+            400 => Status::Failure,
+            401 => Status::Cancelled,
+            _ => Status::Unknown(code),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{lxc, lxc_output, Error, Location, Result};
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+/// LXD storage pool information
+pub struct StoragePool {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub driver: String,
+    pub config: BTreeMap<String, String>,
+    #[serde(default)]
+    pub used_by: Vec<String>,
+    #[serde(default)]
+    pub status: String,
+}
+
+impl StoragePool {
+    /// Retrieve LXD storage pool information from all storage pools
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    ///
+    /// # Return
+    ///
+    /// The LXD storage pool information
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving storage pool info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, StoragePool};
+    ///
+    /// let pools = StoragePool::all(Location::Local).unwrap();
+    /// ```
+    pub fn all(location: Location) -> Result<Vec<Self>> {
+        let json = match location.remote() {
+            None => lxc_output(&["storage", "list", "--format", "json"])?,
+            Some(remote) => lxc_output(&["storage", "list", &format!("{}:", remote), "--format", "json"])?
+        };
+
+        serde_json::from_slice::<Vec<Self>>(&json).map_err(Error::Json)
+    }
+
+    /// Retrieve LXD storage pool information from one storage pool
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the storage pool
+    ///
+    /// # Return
+    ///
+    /// The LXD storage pool information
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving storage pool info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, StoragePool};
+    ///
+    /// let pool = StoragePool::new(Location::Local, "default").unwrap();
+    /// ```
+    pub fn new(location: Location, name: &str) -> Result<Self> {
+        Self::all(location)?.into_iter().find(|pool| pool.name == name).ok_or_else(|| {
+            Error::NotFound(
+                format!("LXD storage: {} not found", name)
+            )
+        })
+    }
+
+    /// Create a storage pool
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `name` - The name of the new storage pool
+    /// * `driver` - The storage driver, `zfs` or `dir` for example
+    /// * `config` - The config keys and values of the storage pool
+    ///
+    /// # Return
+    ///
+    /// The LXD storage pool information of the new storage pool
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while creating the storage pool will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, StoragePool};
+    ///
+    /// let pool = StoragePool::create(Location::Local, "nvme", "dir", &[("source", "/mnt/nvme/lxd")]).unwrap();
+    /// assert_eq!(pool.driver, "dir");
+    /// ```
+    pub fn create(location: Location, name: &str, driver: &str, config: &[(&str, &str)]) -> Result<Self> {
+        let full_name = match location.remote() {
+            None => name.to_string(),
+            Some(remote) => format!("{}:{}", remote, name)
+        };
+        let config: Vec<String> = config.iter().map(|(key, value)| {
+            format!("{}={}", key, value)
+        }).collect();
+
+        let mut args = vec!["storage", "create", &full_name, driver];
+        for arg in config.iter() {
+            args.push(arg);
+        }
+        lxc(&args)?;
+
+        StoragePool::new(location, name)
+    }
+
+    /// Delete the storage pool, which must not be used by any container,
+    /// image or volume
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host the storage pool is on
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while deleting the storage pool will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, StoragePool};
+    ///
+    /// let pool = StoragePool::new(Location::Local, "nvme").unwrap();
+    /// pool.delete(Location::Local).unwrap();
+    /// ```
+    pub fn delete(&self, location: Location) -> Result<()> {
+        let name = match location.remote() {
+            None => self.name.clone(),
+            Some(remote) => format!("{}:{}", remote, self.name)
+        };
+        lxc(&["storage", "delete", &name])
+    }
+
+    /// Check if the storage driver can enforce disk size quotas
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::StoragePool;
+    ///
+    /// let pool = StoragePool {
+    ///     name: "default".to_string(),
+    ///     description: String::new(),
+    ///     driver: "zfs".to_string(),
+    ///     config: Default::default(),
+    ///     used_by: Vec::new(),
+    ///     status: "Created".to_string(),
+    /// };
+    /// assert!(pool.supports_quotas());
+    /// ```
+    pub fn supports_quotas(&self) -> bool {
+        matches!(self.driver.as_str(), "btrfs" | "ceph" | "lvm" | "zfs")
+    }
+
+    /// Get the free space in a storage pool
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `pool` - The name of the storage pool
+    ///
+    /// # Return
+    ///
+    /// The number of bytes available in the storage pool
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving storage pool info will be
+    /// returned, or an error if the driver does not report space usage
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, StoragePool};
+    ///
+    /// let available = StoragePool::available(Location::Local, "default").unwrap();
+    /// println!("{} bytes free", available);
+    /// ```
+    pub fn available(location: Location, pool: &str) -> Result<u64> {
+        let output = match location.remote() {
+            None => lxc_output(&["storage", "info", pool, "--bytes"])?,
+            Some(remote) => lxc_output(&["storage", "info", &format!("{}:{}", remote, pool), "--bytes"])?
+        };
+
+        let text = String::from_utf8_lossy(&output);
+        let used = parse_info_bytes(&text, "space used");
+        let total = parse_info_bytes(&text, "total space");
+        match (used, total) {
+            (Some(used), Some(total)) => Ok(total.saturating_sub(used)),
+            _ => Err(Error::Unsupported(
+                format!("LXD storage: {} does not report space usage", pool)
+            ))
+        }
+    }
+}
+
+/// Parse a byte count from a line of `lxc storage info --bytes`, such as
+/// `  space used: "4734976"`
+fn parse_info_bytes(text: &str, key: &str) -> Option<u64> {
+    text.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name.trim() != key {
+            return None;
+        }
+        value.trim().trim_matches('"').parse().ok()
+    })
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::{lxc, lxc_output, Error, Location, Result};
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+/// LXD trusted certificate information
+pub struct Trust {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub cert_type: String,
+    #[serde(default)]
+    pub restricted: bool,
+    #[serde(default)]
+    pub projects: Vec<String>,
+    pub certificate: String,
+    pub fingerprint: String,
+}
+
+impl Trust {
+    /// Retrieve LXD trusted certificate information from all trusted certificates
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    ///
+    /// # Return
+    ///
+    /// The LXD trusted certificate information
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while retrieving trust info will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Trust};
+    ///
+    /// let certificates = Trust::all(Location::Local).unwrap();
+    /// ```
+    pub fn all(location: Location) -> Result<Vec<Self>> {
+        let json = match location.remote() {
+            None => lxc_output(&["config", "trust", "list", "--format", "json"])?,
+            Some(remote) => lxc_output(&["config", "trust", "list", &format!("{}:", remote), "--format", "json"])?
+        };
+
+        serde_json::from_slice::<Vec<Self>>(&json).map_err(Error::Json)
+    }
+
+    /// Trust a client certificate
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    /// * `certificate` - The path of the PEM encoded certificate
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while adding the certificate will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Trust};
+    ///
+    /// Trust::add(Location::Local, "client.crt").unwrap();
+    /// ```
+    pub fn add<P: AsRef<Path>>(location: Location, certificate: P) -> Result<()> {
+        let certificate = format!("{}", certificate.as_ref().display());
+        match location.remote() {
+            None => lxc(&["config", "trust", "add", &certificate]),
+            Some(remote) => lxc(&["config", "trust", "add", &format!("{}:", remote), &certificate])
+        }
+    }
+
+    /// Stop trusting the certificate
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The location of the host
+    ///
+    /// # Return
+    ///
+    /// An empty tuple on success
+    ///
+    /// # Errors
+    ///
+    /// Errors that are encountered while removing the certificate will be returned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{Location, Trust};
+    ///
+    /// for certificate in Trust::all(Location::Local).unwrap() {
+    ///     if certificate.name == "old-client" {
+    ///         certificate.remove(Location::Local).unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub fn remove(&self, location: Location) -> Result<()> {
+        match location.remote() {
+            None => lxc(&["config", "trust", "remove", &self.fingerprint]),
+            Some(remote) => lxc(&["config", "trust", "remove", &format!("{}:{}", remote, self.fingerprint)])
+        }
+    }
+}
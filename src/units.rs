@@ -0,0 +1,45 @@
+/// Format a byte count as an LXD size string, using the largest binary unit
+/// that represents it exactly
+// u64::is_multiple_of needs Rust 1.87, which is newer than this crate requires
+#[allow(clippy::manual_is_multiple_of)]
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [(&str, u64); 5] = [
+        ("PiB", 1 << 50),
+        ("TiB", 1 << 40),
+        ("GiB", 1 << 30),
+        ("MiB", 1 << 20),
+        ("KiB", 1 << 10),
+    ];
+
+    for (suffix, size) in UNITS.iter() {
+        if bytes != 0 && bytes % *size == 0 {
+            return format!("{}{}", bytes / size, suffix);
+        }
+    }
+    format!("{}B", bytes)
+}
+
+/// Parse an LXD size string, such as `30MB` or `1GiB`, into a byte count
+pub(crate) fn parse_bytes(size: &str) -> Option<u64> {
+    const UNITS: [(&str, u64); 13] = [
+        ("KiB", 1 << 10),
+        ("MiB", 1 << 20),
+        ("GiB", 1 << 30),
+        ("TiB", 1 << 40),
+        ("PiB", 1 << 50),
+        ("kB", 1000),
+        ("KB", 1000),
+        ("MB", 1000 * 1000),
+        ("GB", 1000 * 1000 * 1000),
+        ("TB", 1000 * 1000 * 1000 * 1000),
+        ("PB", 1000 * 1000 * 1000 * 1000 * 1000),
+        ("B", 1),
+        ("", 1),
+    ];
+
+    let size = size.trim();
+    UNITS.iter().find_map(|(suffix, multiplier)| {
+        let number = size.strip_suffix(suffix)?.trim();
+        number.parse::<u64>().ok()?.checked_mul(*multiplier)
+    })
+}
@@ -0,0 +1,73 @@
+use super::{Info, Location, Result, Status};
+
+/// Resource usage totals across the containers of a host
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FleetUsage {
+    /// The number of containers
+    pub containers: usize,
+    /// The number of running containers
+    pub running: usize,
+    /// The memory used by running containers, in bytes
+    pub memory: u64,
+}
+
+impl FleetUsage {
+    /// Sum the resource usage of a list of containers
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lxd::{FleetUsage, Info, Status};
+    ///
+    /// let mut running = Info::default();
+    /// running.status_code = Status::Running.code();
+    /// running.state = Some(Default::default());
+    /// running.state.as_mut().unwrap().memory.insert("usage".to_string(), 1024);
+    ///
+    /// let mut stopped = Info::default();
+    /// stopped.status_code = Status::Stopped.code();
+    ///
+    /// assert_eq!(FleetUsage::from_info(&[running, stopped]), FleetUsage {
+    ///     containers: 2,
+    ///     running: 1,
+    ///     memory: 1024,
+    /// });
+    /// ```
+    pub fn from_info(list: &[Info]) -> Self {
+        let mut usage = FleetUsage::default();
+        for info in list.iter() {
+            usage.containers += 1;
+            if info.status_enum() == Status::Running {
+                usage.running += 1;
+                usage.memory += info.memory_usage().unwrap_or(0);
+            }
+        }
+        usage
+    }
+}
+
+/// Sum the resource usage of all containers on a host
+///
+/// # Arguments
+///
+/// * `location` - The location of the host
+///
+/// # Return
+///
+/// The totals across all containers
+///
+/// # Errors
+///
+/// Errors that are encountered while retrieving info will be returned
+///
+/// # Example
+///
+/// ```
+/// use lxd::{fleet_usage, Location};
+///
+/// let usage = fleet_usage(Location::Local).unwrap();
+/// println!("{} of {} containers running, using {} bytes", usage.running, usage.containers, usage.memory);
+/// ```
+pub fn fleet_usage(location: Location) -> Result<FleetUsage> {
+    Ok(FleetUsage::from_info(&Info::all(location)?))
+}